@@ -33,9 +33,27 @@ fn make_unreserved(alpha: &Vec<String>, digit: &Vec<String>) -> Vec<String> {
     unreserved
 }
 
+fn make_gen_delims() -> Vec<String> {
+    [":", "/", "?", "#", "[", "]", "@"]
+        .iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>()
+}
+
+fn make_sub_delims() -> Vec<String> {
+    ["!", "$", "&", "'", "(", ")", "*", "+", ",", ";", "="]
+        .iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>()
+}
+
 fn print_vec(var_name: &str, vec: &Vec<String>) {
-    println!("static {}: Vec<char> = vec![\n    '{}',\n];",
-             var_name, vec.join("',\n    '"));
+    // Debug formatting takes care of escaping characters like `'`.
+    let chars: Vec<String> = vec.iter()
+        .map(|x| format!("{:?}", x.chars().next().unwrap()))
+        .collect::<Vec<String>>();
+    println!("pub static {}: &[char] = &[\n    {},\n];",
+             var_name, chars.join(",\n    "));
 }
 
 
@@ -43,9 +61,13 @@ fn main() {
     let alpha: Vec<String> = make_alpha();
     let digit: Vec<String> = make_digit();
     let unreserved: Vec<String> = make_unreserved(&alpha, &digit);
+    let gen_delims: Vec<String> = make_gen_delims();
+    let sub_delims: Vec<String> = make_sub_delims();
     println!("//! This file is autogenerated by a tool in the root of the");
     println!("//! project.");
     print_vec("ALPHA", &alpha);
     print_vec("DIGIT", &digit);
     print_vec("UNRESERVED", &unreserved);
+    print_vec("GEN_DELIMS", &gen_delims);
+    print_vec("SUB_DELIMS", &sub_delims);
 }
@@ -0,0 +1,201 @@
+//! The authority component of a URI as described in
+//! https://tools.ietf.org/html/rfc3986#section-3.2.
+use std::fmt;
+
+use error::ParseError;
+use uri::is_ip_literal;
+
+/// The userinfo, host and port of a URI, i.e., everything between the `//`
+/// and the following `/`, `?` or `#`.
+///
+/// # Examples
+///
+/// ```
+/// use rfc3986::authority::Authority;
+/// let authority = Authority::parse("user@[::1]:8080").unwrap();
+/// assert_eq!(Some("user".to_string()), authority.userinfo);
+/// assert_eq!("[::1]", authority.host);
+/// assert_eq!(Some(8080), authority.port);
+/// assert_eq!("user@[::1]:8080", authority.to_string());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Authority {
+    pub userinfo: Option<String>,
+    pub host: String,
+    pub port: Option<u16>,
+}
+
+impl Authority {
+    /// Parse an authority on its own, without the leading `//`. This is the
+    /// same parsing `Uri::parse` applies to the authority of a URI.
+    ///
+    /// The userinfo ends at the `@`. RFC 3986 only allows an `@` in the
+    /// userinfo percent-encoded, so rather than guessing which of several
+    /// ends it, a second `@` is reported as `ParseError::MalformedAuthority`.
+    /// An IP literal in brackets is taken whole, so only a `:` after its
+    /// closing bracket starts the port. A bracket that does not enclose a
+    /// valid IP literal, or a `/`, `?` or `#`, which would end the authority
+    /// in a URI, is reported as `ParseError::MalformedAuthority` too, and a
+    /// port that is not a `u16` as `ParseError::InvalidPort`. An empty port,
+    /// as in `example.com:`, is no port at all.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rfc3986::authority::Authority;
+    /// use rfc3986::error::ParseError;
+    /// assert_eq!("example.com", Authority::parse("example.com").unwrap().host);
+    /// assert_eq!(Err(ParseError::InvalidPort("http".to_string())),
+    ///            Authority::parse("example.com:http"));
+    /// ```
+    pub fn parse(authority: &str) -> Result<Authority, ParseError> {
+        if authority.contains(['/', '?', '#']) {
+            return Err(ParseError::MalformedAuthority(authority.to_string()));
+        }
+        let parts = split_authority(authority).map_err(|failure| failure.error)?;
+        Ok(Authority {
+            userinfo: parts.userinfo.map(|userinfo| userinfo.to_string()),
+            host: parts.host.to_string(),
+            port: parts.port,
+        })
+    }
+}
+
+/// Reassemble the authority, writing the `@` and `:` delimiters only when
+/// the userinfo and port are present.
+impl fmt::Display for Authority {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(ref userinfo) = self.userinfo {
+            write!(f, "{}@", userinfo)?;
+        }
+        f.write_str(&self.host)?;
+        if let Some(port) = self.port {
+            write!(f, ":{}", port)?;
+        }
+        Ok(())
+    }
+}
+
+/// The components of an authority, borrowed from it.
+pub(crate) struct AuthorityParts<'a> {
+    pub(crate) userinfo: Option<&'a str>,
+    pub(crate) host: &'a str,
+    pub(crate) port: Option<u16>,
+    pub(crate) raw_port: Option<&'a str>,
+}
+
+/// A failed split of an authority at byte offset `at`, along with the
+/// userinfo and host read before an invalid port.
+pub(crate) struct AuthorityFailure<'a> {
+    pub(crate) error: ParseError,
+    pub(crate) at: usize,
+    pub(crate) parsed: Option<(Option<&'a str>, &'a str)>,
+}
+
+/// Split `authority` into its userinfo, host and port without allocating
+/// unless it is invalid.
+pub(crate) fn split_authority(authority: &str) -> Result<AuthorityParts<'_>, AuthorityFailure<'_>> {
+    let (userinfo, rest) = match authority.find('@') {
+        Some(at) => (Some(&authority[..at]), &authority[at + 1..]),
+        None => (None, authority),
+    };
+    let offset = authority.len() - rest.len();
+    if let Some(at) = rest.find('@') {
+        return Err(AuthorityFailure {
+            error: ParseError::MalformedAuthority(authority.to_string()),
+            at: offset + at,
+            parsed: None,
+        });
+    }
+
+    // An IP literal contains colons of its own, so it is taken whole and
+    // only a : after its closing bracket starts the port
+    let host_end = match rest.find(']') {
+        Some(close) if rest.starts_with('[') && is_ip_literal(&rest[..close + 1]) &&
+            (close + 1 == rest.len() || rest[close + 1..].starts_with(':')) => close + 1,
+        None if !rest.contains('[') => rest.find(':').unwrap_or(rest.len()),
+        _ => return Err(AuthorityFailure {
+            error: ParseError::MalformedAuthority(rest.to_string()),
+            at: offset,
+            parsed: None,
+        }),
+    };
+    let (host, after_host) = rest.split_at(host_end);
+
+    // Section 3.2.3 allows the port to be empty, which means the default
+    let raw_port = after_host.strip_prefix(':');
+    let port = match raw_port {
+        Some("") => None,
+        Some(port) => match port.parse::<u16>() {
+            Ok(port) => Some(port),
+            Err(_) => return Err(AuthorityFailure {
+                error: ParseError::InvalidPort(port.to_string()),
+                at: offset + host_end + 1,
+                parsed: Some((userinfo, host)),
+            }),
+        },
+        None => None,
+    };
+    Ok(AuthorityParts { userinfo, host, port, raw_port })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Authority;
+    use error::ParseError;
+
+    #[test]
+    fn it_parses_every_component() {
+        assert_eq!(Authority {
+            userinfo: Some("user:pass".to_string()),
+            host: "example.com".to_string(),
+            port: Some(8443),
+        }, Authority::parse("user:pass@example.com:8443").unwrap());
+        assert_eq!(Authority {
+            userinfo: None,
+            host: "".to_string(),
+            port: None,
+        }, Authority::parse("").unwrap());
+    }
+
+    #[test]
+    fn it_parses_ip_literals() {
+        let authority = Authority::parse("[2001:db8::1]:443").unwrap();
+        assert_eq!("[2001:db8::1]", authority.host);
+        assert_eq!(Some(443), authority.port);
+        assert_eq!(Err(ParseError::MalformedAuthority("[::1".to_string())), Authority::parse("[::1"));
+        assert_eq!(Err(ParseError::MalformedAuthority("[::1]x".to_string())),
+                   Authority::parse("u@[::1]x"));
+    }
+
+    #[test]
+    fn it_rejects_delimiters_that_end_an_authority() {
+        for authority in &["example.com/a", "example.com?q", "example.com#f", "u@h:80/"] {
+            assert_eq!(Err(ParseError::MalformedAuthority(authority.to_string())),
+                       Authority::parse(authority));
+        }
+    }
+
+    #[test]
+    fn it_rejects_a_second_at_sign() {
+        for authority in &["a@b@c", "u:p@ss@example.com", "@@h"] {
+            assert_eq!(Err(ParseError::MalformedAuthority(authority.to_string())),
+                       Authority::parse(authority));
+        }
+        assert_eq!(Some("u:p%40ss".to_string()), Authority::parse("u:p%40ss@h").unwrap().userinfo);
+    }
+
+    #[test]
+    fn it_parses_an_empty_port_as_no_port() {
+        for authority in &["h:", "u@h:", "[::1]:"] {
+            assert_eq!(None, Authority::parse(authority).unwrap().port);
+        }
+    }
+
+    #[test]
+    fn it_round_trips_through_display() {
+        for authority in &["example.com", "u@h", "u:p@h:1", "[::1]:8080", ":80"] {
+            assert_eq!(*authority, Authority::parse(authority).unwrap().to_string());
+        }
+    }
+}
@@ -0,0 +1,536 @@
+//! Percent-encoding helpers as described in
+//! https://tools.ietf.org/html/rfc3986#section-2.1.
+use abnf::{SUB_DELIMS, UNRESERVED};
+use error::{DecodeError, ParseError};
+
+/// The parts of a URI that data can be percent-encoded for. Each allows a
+/// different set of characters to appear unencoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Component {
+    /// A user name or password: unreserved characters and sub-delims. The
+    /// `:` separating the two is encoded.
+    UserInfo,
+    /// A registered name: unreserved characters and sub-delims.
+    Host,
+    /// A path: `pchar` and `/`.
+    Path,
+    /// A single path segment: `pchar`, so `/` is encoded.
+    PathSegment,
+    /// A single query key or value: `pchar` and `/`, except for the `&`,
+    /// `=` and `+` used to delimit and interpret pairs.
+    Query,
+    /// A fragment: `pchar`, `/` and `?`.
+    Fragment,
+}
+
+impl Component {
+    /// Check whether `character` may appear unencoded in this component.
+    pub fn allows(self, character: char) -> bool {
+        if UNRESERVED.contains(&character) {
+            return true;
+        }
+        match self {
+            Component::UserInfo | Component::Host => SUB_DELIMS.contains(&character),
+            Component::Path => {
+                SUB_DELIMS.contains(&character) || ":@/".contains(character)
+            }
+            Component::PathSegment => {
+                SUB_DELIMS.contains(&character) || ":@".contains(character)
+            }
+            Component::Query => {
+                SUB_DELIMS.contains(&character) && !"&=+".contains(character) ||
+                    ":@/".contains(character)
+            }
+            Component::Fragment => {
+                SUB_DELIMS.contains(&character) || ":@/?".contains(character)
+            }
+        }
+    }
+}
+
+/// Percent-encode every character of `input` that `component` does not
+/// allow, using the UTF-8 octets of the character.
+pub(crate) fn percent_encode(input: &str, component: Component) -> String {
+    let mut encoded = String::with_capacity(input.len());
+    for character in input.chars() {
+        if component.allows(character) {
+            encoded.push(character);
+        } else {
+            push_encoded(&mut encoded, character);
+        }
+    }
+    encoded
+}
+
+/// Percent-encode the C0 control characters, space, non-ASCII characters and
+/// the characters in `set`, leaving everything else, including existing
+/// triplets, as-is. This is how WHATWG percent-encode sets work.
+pub(crate) fn percent_encode_set(input: &str, set: &str) -> String {
+    let mut encoded = String::with_capacity(input.len());
+    for character in input.chars() {
+        if character <= ' ' || character > '~' || set.contains(character) {
+            push_encoded(&mut encoded, character);
+        } else {
+            encoded.push(character);
+        }
+    }
+    encoded
+}
+
+/// Percent-encode `input` for `component` like `percent_encode`, except
+/// that a `%` starting a valid `%XX` triplet is kept as-is.
+pub(crate) fn reencode(input: &str, component: Component) -> String {
+    let bytes = input.as_bytes();
+    let mut encoded = String::with_capacity(input.len());
+    for (index, character) in input.char_indices() {
+        let starts_triplet = character == '%' &&
+            bytes.get(index + 1).and_then(|&b| hex_value(b)).is_some() &&
+            bytes.get(index + 2).and_then(|&b| hex_value(b)).is_some();
+        if starts_triplet || component.allows(character) {
+            encoded.push(character);
+        } else {
+            push_encoded(&mut encoded, character);
+        }
+    }
+    encoded
+}
+
+/// Append the `%XX` triplets for the UTF-8 octets of `character`.
+fn push_encoded(encoded: &mut String, character: char) {
+    let mut buffer = [0; 4];
+    for byte in character.encode_utf8(&mut buffer).bytes() {
+        encoded.push_str(&format!("%{:02X}", byte));
+    }
+}
+
+/// Percent-encode a user name or password for the userinfo component.
+/// Everything but unreserved characters and sub-delims is encoded, including
+/// the `:` and `@` that would otherwise end the value.
+///
+/// # Examples
+///
+/// ```
+/// use rfc3986::encode::encode_userinfo;
+/// assert_eq!("p%40ss%3Aword", encode_userinfo("p@ss:word"));
+/// ```
+pub fn encode_userinfo(value: &str) -> String {
+    percent_encode(value, Component::UserInfo)
+}
+
+/// Percent-encode a single path segment. Everything outside `pchar` is
+/// encoded, including `/`.
+///
+/// # Examples
+///
+/// ```
+/// use rfc3986::encode::encode_path_segment;
+/// assert_eq!("a%20b%2Fc", encode_path_segment("a b/c"));
+/// ```
+pub fn encode_path_segment(segment: &str) -> String {
+    percent_encode(segment, Component::PathSegment)
+}
+
+/// Percent-encode a single query key or value. Everything outside `pchar`
+/// and `/` is encoded, as are the `&`, `=` and `+` that delimit and
+/// interpret pairs.
+///
+/// # Examples
+///
+/// ```
+/// use rfc3986::encode::encode_query_component;
+/// assert_eq!("a%20b%26c%3Dd", encode_query_component("a b&c=d"));
+/// ```
+pub fn encode_query_component(value: &str) -> String {
+    percent_encode(value, Component::Query)
+}
+
+/// Percent-encode a whole path, keeping its `/` separators.
+///
+/// Every character outside `pchar` other than `/` is encoded, including a
+/// `?` or `#` that would otherwise end the path and a `%` that is already
+/// part of a triplet. Use this to sanitize a path that is already split into
+/// the segments intended.
+///
+/// # Examples
+///
+/// ```
+/// use rfc3986::encode::encode_path;
+/// assert_eq!("/a%20b/c%3Fd", encode_path("/a b/c?d"));
+/// ```
+pub fn encode_path(path: &str) -> String {
+    percent_encode(path, Component::Path)
+}
+
+/// Percent-encode a fragment. `pchar`, `/` and `?` are kept and everything
+/// else is encoded, including spaces, controls, `#`, `<`, `>` and `"`.
+///
+/// # Examples
+///
+/// ```
+/// use rfc3986::encode::encode_fragment;
+/// assert_eq!("a/b?c:d@e", encode_fragment("a/b?c:d@e"));
+/// assert_eq!("a%20b%23c", encode_fragment("a b#c"));
+/// ```
+pub fn encode_fragment(fragment: &str) -> String {
+    percent_encode(fragment, Component::Fragment)
+}
+
+/// Percent-encode `input` for `component` like `Uri::encode_component`,
+/// unless the result would be longer than `max_out` bytes.
+///
+/// The encoded length is computed before anything is allocated, so this is
+/// safe to call on attacker-influenced input that might otherwise triple in
+/// size.
+///
+/// # Examples
+///
+/// ```
+/// use rfc3986::encode::{percent_encode_bounded, Component};
+/// use rfc3986::error::ParseError;
+/// assert_eq!(Ok("a%20b".to_string()), percent_encode_bounded("a b", Component::Path, 5));
+/// assert_eq!(Err(ParseError::EncodedTooLong { length: 5, max: 4 }),
+///            percent_encode_bounded("a b", Component::Path, 4));
+/// ```
+pub fn percent_encode_bounded(input: &str, component: Component, max_out: usize)
+                              -> Result<String, ParseError> {
+    let length: usize = input.chars()
+        .map(|character| if component.allows(character) {
+            character.len_utf8()
+        } else {
+            3 * character.len_utf8()
+        })
+        .sum();
+    if length > max_out {
+        return Err(ParseError::EncodedTooLong { length, max: max_out });
+    }
+    Ok(percent_encode(input, component))
+}
+
+/// Decode every `%XX` triplet in `input` into the octet it represents.
+///
+/// Returns `None` when a `%` is not followed by two hexadecimal digits.
+///
+/// # Examples
+///
+/// ```
+/// use rfc3986::encode::percent_decode;
+/// assert_eq!(Some(b"a b".to_vec()), percent_decode("a%20b"));
+/// assert_eq!(None, percent_decode("a%2"));
+/// ```
+pub fn percent_decode(input: &str) -> Option<Vec<u8>> {
+    let bytes = input.as_bytes();
+    let mut decoded: Vec<u8> = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let high = bytes.get(index + 1).and_then(|&b| hex_value(b));
+            let low = bytes.get(index + 2).and_then(|&b| hex_value(b));
+            match (high, low) {
+                (Some(high), Some(low)) => decoded.push(high << 4 | low),
+                _ => return None,
+            }
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+
+    Some(decoded)
+}
+
+/// Decode `input` with `percent_decode` and interpret the result as UTF-8.
+///
+/// # Examples
+///
+/// ```
+/// use rfc3986::encode::percent_decode_str;
+/// assert_eq!(Some("caf\u{e9}".to_string()), percent_decode_str("caf%C3%A9"));
+/// assert_eq!(None, percent_decode_str("%FF"));
+/// ```
+pub fn percent_decode_str(input: &str) -> Option<String> {
+    percent_decode(input).and_then(|bytes| String::from_utf8(bytes).ok())
+}
+
+/// Decode `input` like `percent_decode_str`, reporting a malformed triplet
+/// or invalid UTF-8 as a `DecodeError`.
+pub(crate) fn percent_decode_component(input: &str) -> Result<String, DecodeError> {
+    let bytes = input.as_bytes();
+    let malformed = input.match_indices('%').map(|(index, _)| index).find(|&index| {
+        bytes.get(index + 1).and_then(|&b| hex_value(b)).is_none() ||
+            bytes.get(index + 2).and_then(|&b| hex_value(b)).is_none()
+    });
+    if let Some(offset) = malformed {
+        return Err(DecodeError::InvalidHexDigit(offset));
+    }
+    let decoded = percent_decode(input).ok_or(DecodeError::InvalidHexDigit(0))?;
+    String::from_utf8(decoded).map_err(|_| DecodeError::InvalidUtf8)
+}
+
+/// Decode `input` like `percent_decode_str`, reporting why it failed.
+///
+/// An overlong UTF-8 sequence, such as `%C0%AF` decoding to `/`, is
+/// rejected as `ParseError::OverlongUtf8` with the offset of the `%` that
+/// starts it, so that it cannot be used to sneak a character past a filter.
+/// Malformed triplets and any other invalid UTF-8 are reported as
+/// `ParseError::InvalidPercentEncoding`.
+///
+/// # Examples
+///
+/// ```
+/// use rfc3986::encode::percent_decode_utf8;
+/// use rfc3986::error::ParseError;
+/// assert_eq!(Ok("/a/".to_string()), percent_decode_utf8("%2Fa%2F"));
+/// assert_eq!(Err(ParseError::OverlongUtf8(2)), percent_decode_utf8("..%C0%AF"));
+/// ```
+pub fn percent_decode_utf8(input: &str) -> Result<String, ParseError> {
+    let bytes = input.as_bytes();
+    let mut decoded: Vec<u8> = Vec::with_capacity(bytes.len());
+    // The offset in `input` that each decoded octet came from
+    let mut offsets: Vec<usize> = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+
+    while index < bytes.len() {
+        offsets.push(index);
+        if bytes[index] == b'%' {
+            let high = bytes.get(index + 1).and_then(|&b| hex_value(b));
+            let low = bytes.get(index + 2).and_then(|&b| hex_value(b));
+            match (high, low) {
+                (Some(high), Some(low)) => decoded.push(high << 4 | low),
+                _ => return Err(ParseError::InvalidPercentEncoding(input.to_string())),
+            }
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+
+    String::from_utf8(decoded).map_err(|error| {
+        let at = error.utf8_error().valid_up_to();
+        if is_overlong_utf8(&error.as_bytes()[at..]) {
+            ParseError::OverlongUtf8(offsets[at])
+        } else {
+            ParseError::InvalidPercentEncoding(input.to_string())
+        }
+    })
+}
+
+/// Check whether `sequence` starts with a UTF-8 sequence that encodes a
+/// code point in more octets than needed.
+fn is_overlong_utf8(sequence: &[u8]) -> bool {
+    match (sequence[0], sequence.get(1)) {
+        (0xC0, _) | (0xC1, _) => true,
+        (0xE0, Some(&next)) => next < 0xA0,
+        (0xF0, Some(&next)) => next < 0x90,
+        _ => false,
+    }
+}
+
+/// Bring every `%XX` triplet in `input` into the form recommended by
+/// section 6.2.2: hexadecimal digits are uppercased and triplets encoding
+/// an unreserved character are decoded. Malformed triplets are kept as-is.
+///
+/// Triplets encoding a reserved character, such as `%2F`, stay encoded,
+/// since decoding them would change how the URI is split.
+///
+/// # Examples
+///
+/// ```
+/// use rfc3986::encode::normalize_percent_encoding;
+/// assert_eq!("~user", normalize_percent_encoding("%7euser"));
+/// assert_eq!("a%2Fb", normalize_percent_encoding("a%2fb"));
+/// ```
+pub fn normalize_percent_encoding(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut normalized = String::with_capacity(input.len());
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let high = bytes.get(index + 1).and_then(|&b| hex_value(b));
+        let low = bytes.get(index + 2).and_then(|&b| hex_value(b));
+        match (bytes[index], high, low) {
+            (b'%', Some(high), Some(low)) => {
+                let decoded = (high << 4 | low) as char;
+                if UNRESERVED.contains(&decoded) {
+                    normalized.push(decoded);
+                } else {
+                    normalized.push_str(&format!("%{:02X}", high << 4 | low));
+                }
+                index += 3;
+            }
+            _ => {
+                let character = input[index..].chars().next().unwrap();
+                normalized.push(character);
+                index += character.len_utf8();
+            }
+        }
+    }
+
+    normalized
+}
+
+/// Collect the octets that `input` encodes as `%XX` triplets, in order.
+/// Malformed triplets are skipped.
+pub(crate) fn encoded_octets(input: &str) -> Vec<u8> {
+    let bytes = input.as_bytes();
+    let mut octets: Vec<u8> = Vec::new();
+    for (index, &byte) in bytes.iter().enumerate() {
+        if byte != b'%' {
+            continue;
+        }
+        let high = bytes.get(index + 1).and_then(|&b| hex_value(b));
+        let low = bytes.get(index + 2).and_then(|&b| hex_value(b));
+        if let (Some(high), Some(low)) = (high, low) {
+            octets.push(high << 4 | low);
+        }
+    }
+    octets
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    match byte {
+        b'0'..=b'9' => Some(byte - b'0'),
+        b'a'..=b'f' => Some(byte - b'a' + 10),
+        b'A'..=b'F' => Some(byte - b'A' + 10),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode_fragment, encode_path, encode_path_segment, encode_query_component,
+                encode_userinfo,
+                normalize_percent_encoding, percent_decode, percent_decode_str,
+                percent_decode_component, percent_decode_utf8, percent_encode,
+                percent_encode_bounded, percent_encode_set, reencode, Component};
+    use error::{DecodeError, ParseError};
+
+    #[test]
+    fn it_decodes_triplets_with_either_case() {
+        assert_eq!(Some(vec![0x2f, 0x2f]), percent_decode("%2f%2F"));
+    }
+
+    #[test]
+    fn it_rejects_truncated_triplets() {
+        assert_eq!(None, percent_decode("%"));
+        assert_eq!(None, percent_decode("%A"));
+        assert_eq!(None, percent_decode("%zz"));
+    }
+
+    #[test]
+    fn it_leaves_plain_text_alone() {
+        assert_eq!(Some("page=3".to_string()), percent_decode_str("page=3"));
+    }
+
+    #[test]
+    fn it_normalizes_percent_encoding() {
+        assert_eq!("~user%2F%C3%A9", normalize_percent_encoding("%7euser%2f%c3%a9"));
+        assert_eq!("100%", normalize_percent_encoding("100%"));
+    }
+
+    #[test]
+    fn it_keeps_reserved_characters_encoded_when_normalizing() {
+        assert_eq!("ABC-._~", normalize_percent_encoding("%41%42%43%2d%2E%5f%7E"));
+        for reserved in &[":", "/", "?", "#", "[", "]", "@", "!", "$", "&", "'", "(", ")", "*", "+", ",", ";", "="] {
+            let encoded = format!("%{:02X}", reserved.as_bytes()[0]);
+            assert_eq!(encoded, normalize_percent_encoding(&encoded.to_lowercase()));
+        }
+        assert_eq!("%25%20%zz", normalize_percent_encoding("%25%20%zz"));
+    }
+
+    #[test]
+    fn it_percent_encodes_utf8_octets() {
+        assert_eq!("caf%C3%A9%20%25", percent_encode("caf\u{e9} %", Component::Path));
+    }
+
+    #[test]
+    fn it_refuses_to_encode_past_the_bound() {
+        let control_characters = "\u{1}".repeat(100);
+        assert_eq!(Err(ParseError::EncodedTooLong { length: 300, max: 299 }),
+                   percent_encode_bounded(&control_characters, Component::Query, 299));
+        assert_eq!(300, percent_encode_bounded(&control_characters, Component::Query, 300)
+                   .unwrap().len());
+    }
+
+    #[test]
+    fn it_counts_multi_byte_characters_when_bounding() {
+        assert_eq!(Err(ParseError::EncodedTooLong { length: 6, max: 5 }),
+                   percent_encode_bounded("\u{e9}", Component::Fragment, 5));
+    }
+
+    #[test]
+    fn it_encodes_a_path_keeping_slashes() {
+        assert_eq!("/a%20b/c%3Fd", encode_path("/a b/c?d"));
+        assert_eq!("/a/b%23c/", encode_path("/a/b#c/"));
+        assert_eq!("a:b@c/d;e=f", encode_path("a:b@c/d;e=f"));
+        assert_eq!("/100%25", encode_path("/100%"));
+    }
+
+    #[test]
+    fn it_rejects_overlong_utf8() {
+        assert_eq!(Err(ParseError::OverlongUtf8(3)), percent_decode_utf8("/..%C0%AF"));
+        assert_eq!(Err(ParseError::OverlongUtf8(0)), percent_decode_utf8("%E0%80%AF"));
+        assert_eq!(Ok("/../".to_string()), percent_decode_utf8("/..%2F"));
+    }
+
+    #[test]
+    fn it_reports_other_invalid_utf8_as_invalid_encoding() {
+        assert_eq!(Err(ParseError::InvalidPercentEncoding("%FF".to_string())),
+                   percent_decode_utf8("%FF"));
+        assert_eq!(Err(ParseError::InvalidPercentEncoding("%2".to_string())),
+                   percent_decode_utf8("%2"));
+        assert_eq!(Ok("caf\u{e9}".to_string()), percent_decode_utf8("caf%C3%A9"));
+    }
+
+    #[test]
+    fn it_reencodes_keeping_valid_triplets() {
+        assert_eq!("a%20b%2Fc%25zz", reencode("a b%2Fc%zz", Component::Path));
+        assert_eq!("100%25", reencode("100%", Component::Fragment));
+    }
+
+    #[test]
+    fn it_reports_where_a_component_fails_to_decode() {
+        assert_eq!(Ok("a b".to_string()), percent_decode_component("a%20b"));
+        assert_eq!(Err(DecodeError::InvalidHexDigit(1)), percent_decode_component("a%"));
+        assert_eq!(Err(DecodeError::InvalidHexDigit(3)), percent_decode_component("%20%A"));
+        assert_eq!(Err(DecodeError::InvalidHexDigit(0)), percent_decode_component("%zz"));
+        assert_eq!(Err(DecodeError::InvalidUtf8), percent_decode_component("%FF"));
+    }
+
+    #[test]
+    fn it_encodes_userinfo() {
+        assert_eq!("a%20b", encode_userinfo("a b"));
+        assert_eq!("a%40b%3Ac%2Fd", encode_userinfo("a@b:c/d"));
+        assert_eq!("j%C3%BCrgen", encode_userinfo("j\u{fc}rgen"));
+    }
+
+    #[test]
+    fn it_encodes_path_segments() {
+        assert_eq!("a%20b", encode_path_segment("a b"));
+        assert_eq!("a@b:c%2Fd", encode_path_segment("a@b:c/d"));
+        assert_eq!("%E2%9C%93", encode_path_segment("\u{2713}"));
+    }
+
+    #[test]
+    fn it_encodes_query_components() {
+        assert_eq!("a%20b", encode_query_component("a b"));
+        assert_eq!("a@b:c/d%3De", encode_query_component("a@b:c/d=e"));
+        assert_eq!("caf%C3%A9", encode_query_component("caf\u{e9}"));
+    }
+
+    #[test]
+    fn it_encodes_exactly_what_a_fragment_disallows() {
+        assert_eq!("a/b?c:d@e", encode_fragment("a/b?c:d@e"));
+        assert_eq!("-._~!$&'()*+,;=", encode_fragment("-._~!$&'()*+,;="));
+        assert_eq!("a%20b%23c", encode_fragment("a b#c"));
+        assert_eq!("%3C%3E%22%00%1F%7F%25%5B%5D%5C%5E%60%7B%7C%7D",
+                   encode_fragment("<>\"\u{0}\u{1f}\u{7f}%[]\\^`{|}"));
+    }
+
+    #[test]
+    fn it_encodes_with_a_whatwg_set() {
+        assert_eq!("a%20%3Cb%3E%%7F%C3%A9%2f", percent_encode_set("a <b>%\u{7f}\u{e9}%2f", "<>"));
+    }
+}
@@ -0,0 +1,251 @@
+//! Errors produced while parsing a `Uri`.
+use std::error::Error;
+use std::fmt;
+
+use uri::Uri;
+
+/// The reasons `Uri::parse` can reject its input.
+///
+/// Some failures happen after a useful prefix of the input has already been
+/// parsed. These are *recoverable*: `Uri::parse_partial` reports them as
+/// `ParseError::Partial` so the caller can keep what was understood and
+/// inspect the remainder. Every other failure is *fatal* and is reported the
+/// same way by both functions.
+///
+/// Recoverable:
+///
+/// * `InvalidPort`, since the scheme, userinfo and host precede the port.
+///
+/// Fatal:
+///
+/// * `ControlCharacter`
+/// * `DuplicateQueryKey`
+/// * `EmptyHost`
+/// * `EmptyHostLabel`
+/// * `EmptyScheme`
+/// * `EncodedTooLong`
+/// * `InvalidFragment`
+/// * `InvalidHost`
+/// * `InvalidIpv4`
+/// * `InvalidPercentEncoding`
+/// * `InvalidPunycode`
+/// * `InvalidQuery`
+/// * `InvalidScheme`
+/// * `LabelTooLong`
+/// * `LimitExceeded`
+/// * `MalformedAuthority`
+/// * `MissingQueryParam`
+/// * `OverlongUtf8`
+/// * `PathTraversal`
+/// * `RootlessPath`
+/// * `TrailingData`, whose offset is all that's needed to re-parse the
+///   clean prefix of the input.
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    /// A strict parse found a control character (`0x00` to `0x1F` or `0x7F`)
+    /// at this byte offset. These are never allowed in a URI.
+    ControlCharacter(usize),
+    /// `Uri::validate_unique_query_keys` or `Uri::require_query_param` found
+    /// this query key more than once.
+    DuplicateQueryKey(String),
+    /// A URI whose scheme needs a host to connect to, such as `http`, had an
+    /// empty one, e.g., `http://user@/path`.
+    EmptyHost,
+    /// This host has an empty label, from a leading dot, two dots in a row
+    /// or more than one trailing dot, e.g., `example..com`.
+    EmptyHostLabel(String),
+    /// The input has a `:` before its `//` but nothing in front of it, e.g.,
+    /// `://example.com`. A relative reference has no `:` there at all.
+    EmptyScheme,
+    /// The port was not a decimal number in the range of a `u16`.
+    InvalidPort(String),
+    /// Percent-encoding a value would produce `length` bytes, more than the
+    /// `max` that was allowed.
+    EncodedTooLong {
+        length: usize,
+        max: usize,
+    },
+    /// This fragment contains a raw `#`, which must be encoded as `%23`.
+    InvalidFragment(String),
+    /// This host is neither an IP literal, an IPv4 address nor a registered
+    /// name made of the characters section 3.2.2 allows.
+    InvalidHost(String),
+    /// This host is written as numbers, like a legacy IPv4 address such as
+    /// `0177.1`, but has too many parts or a part out of range.
+    InvalidIpv4(String),
+    /// A `%` in this value was not followed by two hexadecimal digits, or
+    /// the octets it encodes are not UTF-8 where text was expected.
+    InvalidPercentEncoding(String),
+    /// A host label starting with `xn--` was not valid Punycode.
+    InvalidPunycode(String),
+    /// The query could not be converted to or from a typed value with the
+    /// `serde` feature. This is the message serde gave.
+    InvalidQuery(String),
+    /// This scheme does not start with a letter followed only by letters,
+    /// digits, `+`, `-` and `.`.
+    InvalidScheme(String),
+    /// This host label is longer than the 63 bytes DNS allows, after
+    /// Punycode encoding if it is not ASCII.
+    LabelTooLong(String),
+    /// `Uri::validate_limits` found `count` of the thing named by `limit`,
+    /// more than the `max` that was allowed.
+    LimitExceeded {
+        limit: &'static str,
+        count: usize,
+        max: usize,
+    },
+    /// `Uri::require_query_param` did not find this query key.
+    MissingQueryParam(String),
+    /// The percent-encoded octets starting at this byte offset are an
+    /// overlong UTF-8 sequence, e.g., `%C0%AF` for `/`, a common way to
+    /// slip past filters.
+    OverlongUtf8(usize),
+    /// The authority has a bracket that does not enclose a valid IP literal
+    /// at its start, e.g., the unterminated `[::1`, or more than one `@`.
+    MalformedAuthority(String),
+    /// `Uri::join_segment_safe` was given this segment, which decodes to
+    /// one containing `..`, `/` or `\` and so could leave the directory it
+    /// is joined to.
+    PathTraversal(String),
+    /// The URI has an authority but its path does not begin with `/`.
+    RootlessPath(String),
+    /// A strict parse found a character at this byte offset that cannot
+    /// appear in a URI, so the input is not exactly one URI.
+    TrailingData(usize),
+    /// A recoverable error occurred at byte offset `at`. `parsed` holds the
+    /// components read before that point and `rest` the unparsed input.
+    Partial {
+        parsed: Box<Uri>,
+        rest: String,
+        at: usize,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseError::ControlCharacter(offset) => {
+                write!(f, "control character in the URI at offset {}", offset)
+            }
+            ParseError::DuplicateQueryKey(ref key) => {
+                write!(f, "the query key '{}' appears more than once", key)
+            }
+            ParseError::EmptyHost => write!(f, "the host is empty"),
+            ParseError::EmptyHostLabel(ref host) => {
+                write!(f, "the host '{}' has an empty label", host)
+            }
+            ParseError::EmptyScheme => write!(f, "the scheme is empty"),
+            ParseError::EncodedTooLong { length, max } => {
+                write!(f, "encoding would produce {} bytes, more than {}", length, max)
+            }
+            ParseError::InvalidPort(ref port) => {
+                write!(f, "'{}' is not a valid port", port)
+            }
+            ParseError::InvalidFragment(ref fragment) => {
+                write!(f, "'{}' is not a valid fragment", fragment)
+            }
+            ParseError::InvalidHost(ref host) => {
+                write!(f, "'{}' is not a valid host", host)
+            }
+            ParseError::InvalidIpv4(ref host) => {
+                write!(f, "'{}' is not a valid IPv4 address", host)
+            }
+            ParseError::InvalidPercentEncoding(ref value) => {
+                write!(f, "'{}' is not validly percent-encoded", value)
+            }
+            ParseError::InvalidPunycode(ref label) => {
+                write!(f, "'{}' is not a valid Punycode label", label)
+            }
+            ParseError::InvalidQuery(ref message) => {
+                write!(f, "the query could not be converted: {}", message)
+            }
+            ParseError::InvalidScheme(ref scheme) => {
+                write!(f, "'{}' is not a valid scheme", scheme)
+            }
+            ParseError::LabelTooLong(ref label) => {
+                write!(f, "the host label '{}' is longer than 63 bytes", label)
+            }
+            ParseError::LimitExceeded { limit, count, max } => {
+                write!(f, "the URI has {} {}, more than {}", count, limit, max)
+            }
+            ParseError::MalformedAuthority(ref authority) => {
+                write!(f, "'{}' is not a valid authority", authority)
+            }
+            ParseError::MissingQueryParam(ref key) => {
+                write!(f, "the query key '{}' is missing", key)
+            }
+            ParseError::OverlongUtf8(offset) => {
+                write!(f, "overlong UTF-8 sequence at offset {}", offset)
+            }
+            ParseError::PathTraversal(ref segment) => {
+                write!(f, "the path segment '{}' could escape its directory", segment)
+            }
+            ParseError::RootlessPath(ref path) => {
+                write!(f, "'{}' must begin with '/' when there is an authority", path)
+            }
+            ParseError::TrailingData(offset) => {
+                write!(f, "unexpected data after the URI at offset {}", offset)
+            }
+            ParseError::Partial { ref rest, at, .. } => {
+                write!(f, "could not parse '{}' at offset {}", rest, at)
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// The reasons the `decoded_*` methods of `Uri` can fail to decode a
+/// component.
+#[derive(Debug, PartialEq)]
+pub enum DecodeError {
+    /// The `%` at this byte offset is not followed by two hexadecimal
+    /// digits, e.g., a lone `%` or a truncated `%A`.
+    InvalidHexDigit(usize),
+    /// The decoded octets are not valid UTF-8.
+    InvalidUtf8,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DecodeError::InvalidHexDigit(offset) => {
+                write!(f, "invalid percent-encoded triplet at offset {}", offset)
+            }
+            DecodeError::InvalidUtf8 => write!(f, "the decoded octets are not valid UTF-8"),
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+/// The reasons `rfc3986::host::validate_host` can reject a host.
+#[derive(Debug, PartialEq)]
+pub enum ValidationError {
+    /// This host is neither an IP literal, an IPv4 address nor a registered
+    /// name made of the characters section 3.2.2 allows.
+    InvalidHost(String),
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ValidationError::InvalidHost(ref host) => {
+                write!(f, "'{}' is not a valid host", host)
+            }
+        }
+    }
+}
+
+impl Error for ValidationError {}
+
+/// A host rejected by `validate_host` is reported as
+/// `ParseError::InvalidHost` where a `ParseError` is expected, e.g., by
+/// `UriBuilder::try_finalize`.
+impl From<ValidationError> for ParseError {
+    fn from(error: ValidationError) -> ParseError {
+        match error {
+            ValidationError::InvalidHost(host) => ParseError::InvalidHost(host),
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! Validation of a host against the grammar of
+//! https://tools.ietf.org/html/rfc3986#section-3.2.2.
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use abnf::{SUB_DELIMS, UNRESERVED};
+use error::ValidationError;
+
+/// The forms a valid host can take.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HostKind {
+    /// A registered name, such as `example.com`, which may be empty.
+    RegName,
+    /// A dotted-decimal IPv4 address, such as `192.0.2.1`.
+    IPv4,
+    /// An IPv6 address in brackets, such as `[::1]`.
+    IPv6,
+    /// An address of a future IP version in brackets, such as `[v7.x]`.
+    IPvFuture,
+}
+
+/// Check that `host` is an IP literal, an IPv4 address or a registered name,
+/// and report which one.
+///
+/// A registered name may only contain unreserved characters, sub-delims and
+/// percent-encoded triplets, so names such as `1.2.3` or `01.2.3.4` that
+/// are not IPv4 addresses are registered names, as `Uri::parse` takes them.
+/// Only four dot-separated decimal octets without leading zeros, one of
+/// them above 255, such as `256.1.1.1`, are rejected: they can only be
+/// meant as an IPv4 address. Anything invalid is reported as
+/// `ValidationError::InvalidHost`.
+///
+/// # Examples
+///
+/// ```
+/// use rfc3986::host::{validate_host, HostKind};
+/// assert_eq!(Ok(HostKind::RegName), validate_host("example.com"));
+/// assert_eq!(Ok(HostKind::IPv4), validate_host("192.0.2.1"));
+/// assert_eq!(Ok(HostKind::IPv6), validate_host("[::1]"));
+/// assert!(validate_host("exa mple.com").is_err());
+/// ```
+pub fn validate_host(host: &str) -> Result<HostKind, ValidationError> {
+    let invalid = || ValidationError::InvalidHost(host.to_string());
+    if let Some(literal) = host.strip_prefix('[') {
+        let address = literal.strip_suffix(']').ok_or_else(invalid)?;
+        if address.parse::<Ipv6Addr>().is_ok() {
+            return Ok(HostKind::IPv6);
+        }
+        return if is_ipv_future(address) { Ok(HostKind::IPvFuture) } else { Err(invalid()) };
+    }
+    if host.parse::<Ipv4Addr>().is_ok() {
+        return Ok(HostKind::IPv4);
+    }
+    if is_out_of_range_ipv4(host) {
+        return Err(invalid());
+    }
+    if is_reg_name(host) { Ok(HostKind::RegName) } else { Err(invalid()) }
+}
+
+/// Check whether `host` is written like a dotted-decimal IPv4 address, four
+/// `dec-octet`-shaped parts without leading zeros, but has a part above 255.
+fn is_out_of_range_ipv4(host: &str) -> bool {
+    let parts: Vec<&str> = host.split('.').collect();
+    parts.len() == 4 &&
+        parts.iter().all(|part| {
+            !part.is_empty() && part.len() <= 3 && part.chars().all(|c| c.is_ascii_digit()) &&
+                (part.len() == 1 || !part.starts_with('0'))
+        }) &&
+        parts.iter().any(|part| part.parse::<u16>().is_ok_and(|octet| octet > 255))
+}
+
+/// Check whether `address` is `v`, hexadecimal digits, `.` and then
+/// unreserved characters, sub-delims or `:`.
+fn is_ipv_future(address: &str) -> bool {
+    let version = match address.strip_prefix(['v', 'V']) {
+        Some(version) => version,
+        None => return false,
+    };
+    let dot = match version.find('.') {
+        Some(dot) => dot,
+        None => return false,
+    };
+    let (digits, rest) = (&version[..dot], &version[dot + 1..]);
+    !digits.is_empty() && digits.chars().all(|c| c.is_ascii_hexdigit()) && !rest.is_empty() &&
+        rest.chars().all(|c| UNRESERVED.contains(&c) || SUB_DELIMS.contains(&c) || c == ':')
+}
+
+/// Check whether `host` contains only unreserved characters, sub-delims and
+/// percent-encoded triplets.
+fn is_reg_name(host: &str) -> bool {
+    let bytes = host.as_bytes();
+    host.char_indices().all(|(index, c)| {
+        UNRESERVED.contains(&c) || SUB_DELIMS.contains(&c) ||
+            c == '%' && bytes.len() > index + 2 && bytes[index + 1].is_ascii_hexdigit() &&
+                bytes[index + 2].is_ascii_hexdigit()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate_host, HostKind};
+    use error::ValidationError;
+
+    #[test]
+    fn it_accepts_registered_names() {
+        assert_eq!(Ok(HostKind::RegName), validate_host("example.com"));
+        assert_eq!(Ok(HostKind::RegName), validate_host("ex%C3%A9mple.com"));
+        assert_eq!(Ok(HostKind::RegName), validate_host("a-b_c~d!$&'()*+,;="));
+        assert_eq!(Ok(HostKind::RegName), validate_host(""));
+    }
+
+    #[test]
+    fn it_rejects_invalid_registered_names() {
+        for host in &["exa mple.com", "example.com/", "ex%C3%A.com", "ex%zzmple.com", "h\u{e9}.com"] {
+            assert_eq!(Err(ValidationError::InvalidHost(host.to_string())), validate_host(host));
+        }
+    }
+
+    #[test]
+    fn it_validates_ipv4_addresses() {
+        assert_eq!(Ok(HostKind::IPv4), validate_host("255.255.255.255"));
+        for host in &["256.1.1.1", "1.2.3.999"] {
+            assert_eq!(Err(ValidationError::InvalidHost(host.to_string())), validate_host(host));
+        }
+    }
+
+    #[test]
+    fn it_accepts_numeric_names_that_are_not_ipv4_as_registered_names() {
+        for host in &["1.2.3", "01.2.3.4", "1.2.3.4.5", "1000.1.1.1", "123"] {
+            assert_eq!(Ok(HostKind::RegName), validate_host(host), "validating {}", host);
+        }
+    }
+
+    #[test]
+    fn it_validates_ip_literals() {
+        assert_eq!(Ok(HostKind::IPv6), validate_host("[::1]"));
+        assert_eq!(Ok(HostKind::IPv6), validate_host("[2001:db8::192.0.2.1]"));
+        assert_eq!(Ok(HostKind::IPvFuture), validate_host("[v7.fe80::a+en1]"));
+        for host in &["[::1", "::1", "[::g]", "[v.x]", "[v7.]", "[vz.x]"] {
+            assert_eq!(Err(ValidationError::InvalidHost(host.to_string())), validate_host(host));
+        }
+    }
+}
@@ -1,5 +1,25 @@
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+#[macro_use]
+extern crate serde_derive;
+#[cfg(feature = "serde")]
+extern crate serde_urlencoded;
+
+pub mod abnf;
+pub mod authority;
+pub mod encode;
+pub mod error;
+pub mod host;
+pub mod normalize;
+pub mod public_suffix;
+pub mod punycode;
+pub mod resolve;
+pub mod scheme;
+pub mod shared_uri;
 pub mod uri;
 pub mod uri_builder;
+pub mod uri_ref;
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,528 @@
+//! Normalization of a `Uri` as described in
+//! https://tools.ietf.org/html/rfc3986#section-6.2.2 and
+//! https://tools.ietf.org/html/rfc3986#section-6.2.3.
+use encode::normalize_percent_encoding;
+use scheme::default_port_for_scheme;
+use uri::Uri;
+
+/// Options controlling which steps `Uri::normalize_with` applies.
+///
+/// The defaults follow RFC 3986.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NormalizeOptions {
+    /// Keep the case of the scheme instead of lowercasing it, for schemes
+    /// that are (incorrectly) treated as case-sensitive elsewhere. The host
+    /// is lowercased either way.
+    pub preserve_scheme_case: bool,
+    /// Remove a query that is present but empty, e.g., the `?` in
+    /// `http://example.com/?`. Off by default because it changes the
+    /// serialized form.
+    pub drop_empty_query: bool,
+    /// Remove a fragment that is present but empty, e.g., the `#` in
+    /// `http://example.com/#`. Off by default because it changes the
+    /// serialized form.
+    pub drop_empty_fragment: bool,
+}
+
+impl Uri {
+    /// Normalize the Uri using the default `NormalizeOptions`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rfc3986::uri::Uri;
+    /// let uri = Uri::from_str("HTTPS://Example.COM/Path").normalize();
+    /// assert_eq!(Some("https".to_string()), uri.scheme);
+    /// assert_eq!("example.com", uri.host);
+    /// assert_eq!(Some("/Path".to_string()), uri.path);
+    /// ```
+    pub fn normalize(&self) -> Uri {
+        self.normalize_with(&NormalizeOptions::default())
+    }
+
+    /// Lowercase the scheme and host, which section 6.2.2.1 says are
+    /// case-insensitive, leaving every other component untouched.
+    ///
+    /// The hexadecimal digits of percent-encoded triplets in the host are
+    /// uppercased rather than lowercased, as that section recommends.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rfc3986::uri::Uri;
+    /// let uri = Uri::from_str("HTTPS://EXAMPLE.COM/Path%2f").normalize_case();
+    /// assert_eq!("https://example.com/Path%2f", uri.to_string());
+    /// ```
+    pub fn normalize_case(&self) -> Uri {
+        Uri {
+            scheme: self.scheme.as_ref().map(|scheme| scheme.to_ascii_lowercase()),
+            host: lowercase_host(&self.host),
+            ..self.clone()
+        }
+    }
+
+    /// Drop the port when it is the default port of the scheme, which
+    /// section 6.2.3 says is equivalent to omitting it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rfc3986::uri::Uri;
+    /// assert_eq!(None, Uri::from_str("http://example.com:80/").normalize_port().port);
+    /// assert_eq!(Some(8080), Uri::from_str("http://example.com:8080/").normalize_port().port);
+    /// ```
+    pub fn normalize_port(&self) -> Uri {
+        let default_port = self.scheme.as_ref().and_then(|scheme| default_port_for_scheme(scheme));
+        Uri {
+            port: self.port.filter(|&port| Some(port) != default_port),
+            raw_port: self.raw_port.clone().filter(|_| self.port != default_port),
+            ..self.clone()
+        }
+    }
+
+    /// Normalize the percent-encoding of the path, query and fragment with
+    /// `normalize_percent_encoding`, as sections 6.2.2.1 and 6.2.2.2
+    /// describe, leaving every other component untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rfc3986::uri::Uri;
+    /// let uri = Uri::from_str("http://example.com/%7euser/a%2fb?q=%41#%7e").normalize_percent();
+    /// assert_eq!("http://example.com/~user/a%2Fb?q=A#~", uri.to_string());
+    /// ```
+    pub fn normalize_percent(&self) -> Uri {
+        Uri {
+            path: self.path.as_ref().map(|path| normalize_percent_encoding(path)),
+            query: self.query.as_ref().map(|query| normalize_percent_encoding(query)),
+            fragment: self.fragment.as_ref().map(|fragment| normalize_percent_encoding(fragment)),
+            ..self.clone()
+        }
+    }
+
+    /// Normalize the Uri, applying the steps selected by `options`.
+    ///
+    /// Percent-encoded triplets in the userinfo, path, query and fragment
+    /// are uppercased and those encoding unreserved characters are decoded.
+    /// Parsing and `Display` never do this on their own, so a URI that is
+    /// not normalized is reproduced exactly as it was written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rfc3986::normalize::NormalizeOptions;
+    /// use rfc3986::uri::Uri;
+    /// let options = NormalizeOptions { preserve_scheme_case: true, ..NormalizeOptions::default() };
+    /// let uri = Uri::from_str("MyScheme://Example.COM/").normalize_with(&options);
+    /// assert_eq!(Some("MyScheme".to_string()), uri.scheme);
+    /// assert_eq!("example.com", uri.host);
+    /// ```
+    pub fn normalize_with(&self, options: &NormalizeOptions) -> Uri {
+        let mut uri = self.clone();
+
+        if !options.preserve_scheme_case {
+            uri.scheme = uri.scheme.map(|scheme| scheme.to_ascii_lowercase());
+        }
+        uri.host = lowercase_host(&uri.host);
+        uri.raw_port = None;
+        uri.userinfo = uri.userinfo.map(|userinfo| normalize_percent_encoding(&userinfo));
+        uri.path = uri.path.map(|path| normalize_percent_encoding(&path));
+        uri.query = uri.query.map(|query| normalize_percent_encoding(&query));
+        uri.fragment = uri.fragment.map(|fragment| normalize_percent_encoding(&fragment));
+        if options.drop_empty_query && uri.query.as_ref().is_some_and(|query| query.is_empty()) {
+            uri.query = None;
+        }
+        if options.drop_empty_fragment &&
+           uri.fragment.as_ref().is_some_and(|fragment| fragment.is_empty()) {
+            uri.fragment = None;
+        }
+
+        uri
+    }
+
+    /// Check whether `normalize` would leave the Uri unchanged, so that
+    /// URIs already in canonical form can be skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rfc3986::uri::Uri;
+    /// assert!(Uri::from_str("https://example.com/a%2Fb").is_normalized());
+    /// assert!(!Uri::from_str("https://Example.com/a").is_normalized());
+    /// ```
+    pub fn is_normalized(&self) -> bool {
+        self.normalize() == *self
+    }
+
+    /// Canonicalize the Uri the way web crawlers do before deduplicating
+    /// URLs or matching them against robots.txt rules.
+    ///
+    /// Starting from `normalize`, which lowercases the scheme and host and
+    /// decodes percent-encoded unreserved characters, this:
+    ///
+    /// 1. removes a port equal to the scheme's default;
+    /// 1. removes dot-segments from the path;
+    /// 1. uses `/` as the path when there is an authority but no path;
+    /// 1. sorts the `&`-separated query parameters by their raw text,
+    ///    dropping empty ones, and removes the query if none are left;
+    /// 1. removes the fragment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rfc3986::uri::Uri;
+    /// let uri = Uri::from_str("HTTP://Example.com:80/a/../b?z=1&a=2#top");
+    /// assert_eq!("http://example.com/b?a=2&z=1", uri.crawler_canonical().to_string());
+    /// ```
+    pub fn crawler_canonical(&self) -> Uri {
+        let mut uri = self.normalize().normalize_port();
+
+        uri.path = match uri.path {
+            Some(ref path) if !path.is_empty() => Some(remove_dot_segments(path)),
+            _ if uri.has_authority() => Some("/".to_string()),
+            _ => None,
+        };
+        uri.query = uri.query.and_then(|query| {
+            let mut params: Vec<&str> = query.split('&').filter(|param| !param.is_empty()).collect();
+            params.sort();
+            if params.is_empty() { None } else { Some(params.join("&")) }
+        });
+        uri.fragment = None;
+
+        uri
+    }
+
+    /// Check whether two URIs are equal once both are normalized with
+    /// `options`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rfc3986::normalize::NormalizeOptions;
+    /// use rfc3986::uri::Uri;
+    /// let options = NormalizeOptions { drop_empty_query: true, ..NormalizeOptions::default() };
+    /// let uri = Uri::from_str("HTTP://Example.com/?");
+    /// assert!(uri.eq_under(&Uri::from_str("http://example.com/"), &options));
+    /// ```
+    pub fn eq_under(&self, other: &Uri, options: &NormalizeOptions) -> bool {
+        self.normalize_with(options) == other.normalize_with(options)
+    }
+
+    /// Check whether two URIs are equivalent under the syntax- and
+    /// scheme-based normalization of section 6.2, unlike `==`, which
+    /// compares the components exactly as written.
+    ///
+    /// Before comparing, both URIs have:
+    ///
+    /// 1. their scheme and host lowercased (section 6.2.2.1);
+    /// 1. the hexadecimal digits of percent-encoded triplets uppercased
+    ///    (section 6.2.2.1);
+    /// 1. percent-encoded unreserved characters decoded (section 6.2.2.2);
+    /// 1. dot-segments removed from the path (section 6.2.2.3);
+    /// 1. a port equal to the scheme's default removed (section 6.2.3).
+    ///
+    /// An empty path is not taken to equal `/`, and nothing from section
+    /// 6.2.4, such as resolving the host, is applied.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rfc3986::uri::Uri;
+    /// let uri = Uri::from_str("HTTP://Example.COM:80/a/./%7euser");
+    /// assert!(uri.eq_normalized(&Uri::from_str("http://example.com/a/~user")));
+    /// assert!(uri != Uri::from_str("http://example.com/a/~user"));
+    /// ```
+    pub fn eq_normalized(&self, other: &Uri) -> bool {
+        fn normalized(uri: &Uri) -> Uri {
+            uri.normalize().normalize_port().normalize_path()
+        }
+        normalized(self) == normalized(other)
+    }
+
+    /// Check whether the paths of two URIs are equivalent, ignoring every
+    /// other component.
+    ///
+    /// Both paths have their dot-segments removed as in section 5.2.4 and
+    /// their percent-encoding normalized before they're compared. A missing
+    /// path equals an empty one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rfc3986::uri::Uri;
+    /// let uri = Uri::from_str("https://a.example.com/a/./b");
+    /// assert!(uri.path_equivalent(&Uri::from_str("http://b.example.com/a/b")));
+    /// ```
+    pub fn path_equivalent(&self, other: &Uri) -> bool {
+        fn normalized_path(uri: &Uri) -> String {
+            let path = uri.path.as_ref().map_or("", |path| path.as_str());
+            remove_dot_segments(&normalize_percent_encoding(path))
+        }
+        normalized_path(self) == normalized_path(other)
+    }
+
+    /// Remove the `.` and `..` segments from the path with
+    /// `remove_dot_segments`, leaving every other component untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rfc3986::uri::Uri;
+    /// let uri = Uri::from_str("http://example.com/a/b/c/./../../g?q").normalize_path();
+    /// assert_eq!("http://example.com/a/g?q", uri.to_string());
+    /// ```
+    pub fn normalize_path(&self) -> Uri {
+        Uri {
+            path: self.path.as_ref().map(|path| remove_dot_segments(path)),
+            ..self.clone()
+        }
+    }
+}
+
+/// Remove the `.` and `..` segments from `path` following the algorithm of
+/// section 5.2.4. A `..` above the root is discarded and a final `.` or
+/// `..` leaves a trailing `/`.
+///
+/// # Examples
+///
+/// ```
+/// use rfc3986::normalize::remove_dot_segments;
+/// assert_eq!("/a/g", remove_dot_segments("/a/b/c/./../../g"));
+/// assert_eq!("/a/b/", remove_dot_segments("/a/b/c/.."));
+/// ```
+pub fn remove_dot_segments(path: &str) -> String {
+    let mut input = path;
+    let mut output = String::with_capacity(path.len());
+    while !input.is_empty() {
+        if input.starts_with("../") {
+            input = &input[3..];
+        } else if input.starts_with("./") || input.starts_with("/./") {
+            input = &input[2..];
+        } else if input == "/." {
+            input = "/";
+        } else if input.starts_with("/../") || input == "/.." {
+            input = if input == "/.." { "/" } else { &input[3..] };
+            let last_segment = output.rfind('/').unwrap_or(0);
+            output.truncate(last_segment);
+        } else if input == "." || input == ".." {
+            input = "";
+        } else {
+            let start = if input.starts_with('/') { 1 } else { 0 };
+            let end = input[start..].find('/').map_or(input.len(), |end| end + start);
+            output.push_str(&input[..end]);
+            input = &input[end..];
+        }
+    }
+    output
+}
+
+/// Lowercase a host while keeping the hexadecimal digits of its
+/// percent-encoded triplets uppercase, as section 6.2.2.1 recommends.
+fn lowercase_host(host: &str) -> String {
+    let mut lowercased = String::with_capacity(host.len());
+    let mut in_triplet = 0;
+    for character in host.chars() {
+        if character == '%' {
+            in_triplet = 2;
+            lowercased.push(character);
+        } else if in_triplet > 0 {
+            in_triplet -= 1;
+            lowercased.push(character.to_ascii_uppercase());
+        } else {
+            lowercased.push(character.to_ascii_lowercase());
+        }
+    }
+    lowercased
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{remove_dot_segments, NormalizeOptions};
+    use uri::Uri;
+
+    #[test]
+    fn it_lowercases_the_scheme_and_host_by_default() {
+        let uri = Uri::from_str("MyScheme://EXAMPLE.com/A").normalize();
+        assert_eq!(Some("myscheme".to_string()), uri.scheme);
+        assert_eq!("example.com", uri.host);
+        assert_eq!(Some("/A".to_string()), uri.path);
+    }
+
+    #[test]
+    fn it_preserves_the_scheme_case_when_asked() {
+        let options = NormalizeOptions { preserve_scheme_case: true, ..NormalizeOptions::default() };
+        let uri = Uri::from_str("MyScheme://EXAMPLE.com/A").normalize_with(&options);
+        assert_eq!(Some("MyScheme".to_string()), uri.scheme);
+        assert_eq!("example.com", uri.host);
+    }
+
+    #[test]
+    fn it_normalizes_only_the_case_of_the_scheme_and_host() {
+        let uri = Uri::from_str("HtTpS://WWW.Example.COM/A%2fB?Q=%2f#F").normalize_case();
+        assert_eq!(Uri::from_str("https://www.example.com/A%2fB?Q=%2f#F"), uri);
+        assert_eq!("ex%C3%A9.com", Uri::from_str("http://EX%c3%a9.COM/").normalize_case().host);
+    }
+
+    #[test]
+    fn it_keeps_percent_encoded_host_triplets_uppercase() {
+        let uri = Uri::from_str("http://EX%c3%a9MPLE.com/").normalize();
+        assert_eq!("ex%C3%A9mple.com", uri.host);
+    }
+
+    #[test]
+    fn it_drops_only_the_default_port() {
+        let uri = Uri::from_str("http://x:80/a").normalize_port();
+        assert_eq!(None, uri.port);
+        assert_eq!("http://x/a", uri.to_string());
+        assert_eq!(Some(8080), Uri::from_str("http://x:8080/a").normalize_port().port);
+        assert_eq!(Some(80), Uri::from_str("https://x:80/a").normalize_port().port);
+        assert_eq!(Some(80), Uri::from_str("foo://x:80/a").normalize_port().port);
+    }
+
+    #[test]
+    fn it_only_changes_percent_encoding_case_when_normalizing() {
+        let url = "http://example.com/a%2fb?c=%2f#%2f";
+        let uri = Uri::from_str(url);
+        assert_eq!(url, uri.to_string());
+        assert_eq!("http://example.com/a%2Fb?c=%2F#%2F", uri.normalize().to_string());
+    }
+
+    #[test]
+    fn it_drops_empty_query_and_fragment_only_when_asked() {
+        let uri = Uri::from_str("http://h/?#");
+        assert_eq!("http://h/?#", uri.normalize().to_string());
+
+        let options = NormalizeOptions {
+            drop_empty_query: true,
+            drop_empty_fragment: true,
+            ..NormalizeOptions::default()
+        };
+        assert_eq!("http://h/", uri.normalize_with(&options).to_string());
+    }
+
+    #[test]
+    fn it_removes_dot_segments() {
+        assert_eq!("/a/g", remove_dot_segments("/a/b/c/./../../g"));
+        assert_eq!("mid/6", remove_dot_segments("mid/content=5/../6"));
+        assert_eq!("/", remove_dot_segments("/a/.."));
+        assert_eq!("/b", remove_dot_segments("/../b"));
+    }
+
+    #[test]
+    fn it_removes_leading_and_trailing_dot_segments() {
+        assert_eq!("g", remove_dot_segments("../../g"));
+        assert_eq!("g", remove_dot_segments("./g"));
+        assert_eq!("/a/b/", remove_dot_segments("/a/b/."));
+        assert_eq!("/a/", remove_dot_segments("/a/b/.."));
+        assert_eq!("", remove_dot_segments("."));
+        assert_eq!("", remove_dot_segments(".."));
+    }
+
+    #[test]
+    fn it_normalizes_only_the_percent_encoding_of_the_path_query_and_fragment() {
+        let uri = Uri::from_str("HTTP://%7eu@Example.com/%7euser/%2f?a=%2f&b=%7e#%41").normalize_percent();
+        assert_eq!("HTTP://%7eu@Example.com/~user/%2F?a=%2F&b=~#A", uri.to_string());
+        assert_eq!(Uri::from_str("/a"), Uri::from_str("/a").normalize_percent());
+    }
+
+    #[test]
+    fn it_normalizes_only_the_path() {
+        let uri = Uri::from_str("HTTP://Example.com/a/./b/../c?x=/./#/../");
+        assert_eq!("HTTP://Example.com/a/c?x=/./#/../", uri.normalize_path().to_string());
+        assert_eq!(None, Uri::from_str("http://example.com").normalize_path().path);
+    }
+
+    #[test]
+    fn it_compares_paths_after_removing_dot_segments() {
+        let uri = Uri::from_str("https://a.example.com/a/./b");
+        assert!(uri.path_equivalent(&Uri::from_str("http://b.example.com/a/b")));
+        assert!(uri.path_equivalent(&Uri::from_str("http://h/a/c/../b")));
+        assert!(!uri.path_equivalent(&Uri::from_str("http://h/a/b/c")));
+    }
+
+    #[test]
+    fn it_compares_paths_after_normalizing_percent_encoding() {
+        let uri = Uri::from_str("http://h/%7euser/a%2fb");
+        assert!(uri.path_equivalent(&Uri::from_str("http://h/~user/a%2Fb")));
+        assert!(!uri.path_equivalent(&Uri::from_str("http://h/~user/a/b")));
+    }
+
+    #[test]
+    fn it_compares_under_the_default_options() {
+        let uri = Uri::from_str("HTTP://Example.com/%7ea?");
+        assert!(uri.eq_under(&Uri::from_str("http://example.com/~a?"), &NormalizeOptions::default()));
+        assert!(!uri.eq_under(&Uri::from_str("http://example.com/~a"), &NormalizeOptions::default()));
+    }
+
+    #[test]
+    fn it_compares_under_custom_options() {
+        let uri = Uri::from_str("MyScheme://h/a?#");
+        let other = Uri::from_str("myscheme://h/a");
+        let drop_empty = NormalizeOptions {
+            drop_empty_query: true,
+            drop_empty_fragment: true,
+            ..NormalizeOptions::default()
+        };
+        assert!(uri.eq_under(&other, &drop_empty));
+
+        let preserve_case = NormalizeOptions { preserve_scheme_case: true, ..drop_empty };
+        assert!(!uri.eq_under(&other, &preserve_case));
+    }
+
+    #[test]
+    fn it_compares_variants_of_the_same_uri_as_normalized() {
+        let uri = Uri::from_str("http://example.com/a/b%2Fc?q=%7e#f");
+        for variant in &["HTTP://EXAMPLE.COM/a/b%2Fc?q=%7e#f",
+                         "http://example.com:80/a/b%2Fc?q=%7e#f",
+                         "http://example.com/a/b%2fc?q=%7E#f",
+                         "http://example.com/a/b%2Fc?q=~#f",
+                         "http://example.com/x/../a/./b%2Fc?q=%7e#f",
+                         "Http://Example.com:80/a/./b%2fc?q=~#f"] {
+            assert!(uri.eq_normalized(&Uri::from_str(variant)), "comparing with {}", variant);
+        }
+    }
+
+    #[test]
+    fn it_keeps_differences_that_normalization_does_not_remove() {
+        let uri = Uri::from_str("http://example.com/a");
+        for other in &["http://example.com:8080/a",
+                       "http://example.com/A",
+                       "http://example.com/a/",
+                       "https://example.com/a",
+                       "http://example.com/a?"] {
+            assert!(!uri.eq_normalized(&Uri::from_str(other)), "comparing with {}", other);
+        }
+        assert!(!Uri::from_str("http://example.com").eq_normalized(&Uri::from_str("http://example.com/")));
+    }
+
+    #[test]
+    fn it_canonicalizes_a_messy_url_for_crawlers() {
+        let uri = Uri::from_str("HTTPS://WWW.Example.COM:443/a/./b/../%7euser/?z=3&&a=1&m=%2f#frag");
+        assert_eq!("https://www.example.com/a/~user/?a=1&m=%2F&z=3",
+                   uri.crawler_canonical().to_string());
+    }
+
+    #[test]
+    fn it_handles_edge_cases_when_canonicalizing_for_crawlers() {
+        assert_eq!("http://example.com:8080/",
+                   Uri::from_str("http://example.com:8080").crawler_canonical().to_string());
+        assert_eq!("http://example.com/a",
+                   Uri::from_str("http://example.com/a?&#x").crawler_canonical().to_string());
+        assert_eq!("http://example.com/a?b=1&b=2",
+                   Uri::from_str("http://example.com/a?b=2&b=1").crawler_canonical().to_string());
+    }
+
+    #[test]
+    fn it_detects_normalized_uris() {
+        assert!(Uri::from_str("https://example.com/a/b?c=%2F#d").is_normalized());
+        assert!(Uri::from_str("//example.com/").is_normalized());
+    }
+
+    #[test]
+    fn it_detects_uris_needing_normalization() {
+        assert!(!Uri::from_str("https://EXAMPLE.com/a").is_normalized());
+        assert!(!Uri::from_str("HTTPS://example.com/a").is_normalized());
+        assert!(!Uri::from_str("https://example.com/a%2fb").is_normalized());
+        assert!(!Uri::from_str("https://example.com/%7Euser").is_normalized());
+    }
+}
@@ -0,0 +1,165 @@
+//! Public suffix lookups based on the rules and algorithm published at
+//! https://publicsuffix.org/list/.
+//!
+//! Only a subset of the list is embedded: the multi-label suffixes of the
+//! most common country-code registries and a handful of wildcard and
+//! exception rules. The list's default `*` rule is applied only within the
+//! top-level domains that the table covers. Any other host, e.g., one under
+//! `il` whose `co.il` suffix is not embedded, has no answer at all, so that
+//! a public suffix is never mistaken for a registrable domain.
+use std::net::Ipv4Addr;
+
+static RULES: &[&str] = &[
+    // Generic top-level domains
+    "com", "net", "org", "edu", "gov", "mil", "int", "info", "biz", "io",
+    // Australia
+    "au", "com.au", "net.au", "org.au", "edu.au", "gov.au", "asn.au", "id.au",
+    // Brazil
+    "br", "com.br", "net.br", "org.br", "gov.br", "edu.br",
+    // China
+    "cn", "com.cn", "net.cn", "org.cn", "gov.cn", "edu.cn",
+    // Cook Islands
+    "*.ck", "!www.ck",
+    // India
+    "in", "co.in", "net.in", "org.in", "gov.in", "ac.in",
+    // Japan
+    "jp", "co.jp", "ne.jp", "or.jp", "ac.jp", "go.jp", "ad.jp", "ed.jp",
+    "gr.jp", "lg.jp", "*.kawasaki.jp", "!city.kawasaki.jp",
+    // South Korea
+    "kr", "co.kr", "ne.kr", "or.kr", "go.kr", "ac.kr",
+    // Mexico
+    "mx", "com.mx", "net.mx", "org.mx", "gob.mx", "edu.mx",
+    // New Zealand
+    "nz", "co.nz", "net.nz", "org.nz", "govt.nz", "ac.nz", "school.nz",
+    // Singapore
+    "sg", "com.sg", "net.sg", "org.sg", "gov.sg", "edu.sg",
+    // Turkey
+    "tr", "com.tr", "net.tr", "org.tr", "gov.tr", "edu.tr",
+    // United Kingdom
+    "uk", "co.uk", "org.uk", "me.uk", "ltd.uk", "plc.uk", "net.uk", "ac.uk",
+    "gov.uk", "nhs.uk", "police.uk", "sch.uk",
+    // United States
+    "us", "ak.us", "al.us", "ca.us", "ny.us", "tx.us", "wa.us",
+    // South Africa
+    "za", "co.za", "net.za", "org.za", "gov.za", "ac.za", "edu.za",
+];
+
+/// Find how many of the trailing `labels` form the public suffix, or
+/// `None` when no rule covers the top-level domain.
+fn suffix_label_count(labels: &[&str]) -> Option<usize> {
+    let top_level = labels[labels.len() - 1];
+    if !RULES.iter().any(|rule| rule.rsplit('.').next() == Some(top_level)) {
+        return None;
+    }
+    // The default rule, `*`, makes the last label a public suffix.
+    let mut count = 1;
+    for length in 1..labels.len() + 1 {
+        let candidate = labels[labels.len() - length..].join(".");
+        if RULES.contains(&format!("!{}", candidate).as_str()) {
+            // Exception rules always win and exclude their leftmost label.
+            return Some(length - 1);
+        }
+        let wildcard = if length > 1 {
+            format!("*.{}", labels[labels.len() - length + 1..].join("."))
+        } else {
+            "*".to_string()
+        };
+        if RULES.contains(&candidate.as_str()) || RULES.contains(&wildcard.as_str()) {
+            count = length;
+        }
+    }
+    Some(count)
+}
+
+/// Find the public suffix of `host`, e.g., `co.uk` for `www.example.co.uk`.
+///
+/// Returns `None` for IP addresses, empty hosts and hosts under a
+/// top-level domain the embedded rules do not cover. The host is compared
+/// case-insensitively and a trailing `.` is ignored.
+///
+/// # Examples
+///
+/// ```
+/// use rfc3986::public_suffix::public_suffix;
+/// assert_eq!(Some("co.uk".to_string()), public_suffix("www.example.co.uk"));
+/// ```
+pub fn public_suffix(host: &str) -> Option<String> {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    if host.is_empty() || host.starts_with('[') || host.parse::<Ipv4Addr>().is_ok() {
+        return None;
+    }
+    let labels: Vec<&str> = host.split('.').collect();
+    let count = suffix_label_count(&labels)?;
+    Some(labels[labels.len() - count..].join("."))
+}
+
+/// Find the registrable domain of `host`: its public suffix plus one more
+/// label, e.g., `example.co.uk` for `www.example.co.uk`.
+///
+/// Returns `None` for IP addresses, empty hosts, hosts that are
+/// themselves a public suffix and hosts under a top-level domain the
+/// embedded rules do not cover.
+///
+/// # Examples
+///
+/// ```
+/// use rfc3986::public_suffix::registrable_domain;
+/// assert_eq!(Some("example.co.uk".to_string()),
+///            registrable_domain("www.example.co.uk"));
+/// assert_eq!(None, registrable_domain("co.uk"));
+/// ```
+pub fn registrable_domain(host: &str) -> Option<String> {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    if host.is_empty() || host.starts_with('[') || host.parse::<Ipv4Addr>().is_ok() {
+        return None;
+    }
+    let labels: Vec<&str> = host.split('.').collect();
+    let count = suffix_label_count(&labels)? + 1;
+    if count > labels.len() {
+        return None;
+    }
+    Some(labels[labels.len() - count..].join("."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{public_suffix, registrable_domain};
+
+    #[test]
+    fn it_finds_multi_level_suffixes() {
+        assert_eq!(Some("com.au".to_string()), public_suffix("a.b.example.com.au"));
+        assert_eq!(Some("example.com.au".to_string()),
+                   registrable_domain("a.b.example.com.au"));
+    }
+
+    #[test]
+    fn it_falls_back_to_the_default_rule_within_a_covered_top_level_domain() {
+        assert_eq!(Some("uk".to_string()), public_suffix("www.example.uk"));
+        assert_eq!(Some("example.uk".to_string()), registrable_domain("www.example.uk"));
+        assert_eq!(Some("example.ck".to_string()), public_suffix("www.example.ck"));
+    }
+
+    #[test]
+    fn it_gives_no_answer_for_top_level_domains_it_does_not_cover() {
+        for host in &["a.co.il", "www.test.example", "shop.com.ar", "a.co.id", "il"] {
+            assert_eq!(None, public_suffix(host), "finding the suffix of {}", host);
+            assert_eq!(None, registrable_domain(host), "finding the domain of {}", host);
+        }
+    }
+
+    #[test]
+    fn it_applies_wildcard_and_exception_rules() {
+        assert_eq!(Some("foo.kawasaki.jp".to_string()), public_suffix("www.foo.kawasaki.jp"));
+        assert_eq!(None, registrable_domain("foo.kawasaki.jp"));
+        assert_eq!(Some("kawasaki.jp".to_string()), public_suffix("city.kawasaki.jp"));
+        assert_eq!(Some("city.kawasaki.jp".to_string()),
+                   registrable_domain("www.city.kawasaki.jp"));
+    }
+
+    #[test]
+    fn it_ignores_ip_addresses() {
+        assert_eq!(None, registrable_domain("127.0.0.1"));
+        assert_eq!(None, registrable_domain("[::1]"));
+        assert_eq!(None, registrable_domain(""));
+    }
+}
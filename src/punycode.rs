@@ -0,0 +1,187 @@
+//! The Punycode encoding from https://tools.ietf.org/html/rfc3492, used by
+//! IDNA to represent Unicode host labels as ASCII.
+use std::char;
+
+const BASE: u32 = 36;
+const T_MIN: u32 = 1;
+const T_MAX: u32 = 26;
+const SKEW: u32 = 38;
+const DAMP: u32 = 700;
+const INITIAL_BIAS: u32 = 72;
+const INITIAL_N: u32 = 128;
+
+/// Decode a Punycode string (without the `xn--` prefix) into Unicode.
+///
+/// Returns `None` when `input` is not valid Punycode.
+///
+/// # Examples
+///
+/// ```
+/// use rfc3986::punycode;
+/// assert_eq!(Some("m\u{fc}nchen".to_string()), punycode::decode("mnchen-3ya"));
+/// ```
+pub fn decode(input: &str) -> Option<String> {
+    let (basic, extended) = match input.rfind('-') {
+        Some(position) => (&input[..position], &input[position + 1..]),
+        None => ("", input),
+    };
+    if !basic.is_ascii() {
+        return None;
+    }
+
+    let mut output: Vec<char> = basic.chars().collect();
+    let mut n = INITIAL_N;
+    let mut i: u32 = 0;
+    let mut bias = INITIAL_BIAS;
+    let mut digits = extended.bytes();
+
+    while digits.len() > 0 {
+        let old_i = i;
+        let mut weight: u32 = 1;
+        let mut k = BASE;
+        loop {
+            let digit = digits.next().and_then(decode_digit)?;
+            i = i.checked_add(digit.checked_mul(weight)?)?;
+            let threshold = threshold(k, bias);
+            if digit < threshold {
+                break;
+            }
+            weight = weight.checked_mul(BASE - threshold)?;
+            k += BASE;
+        }
+
+        let length = output.len() as u32 + 1;
+        bias = adapt(i - old_i, length, old_i == 0);
+        n = n.checked_add(i / length)?;
+        i %= length;
+        output.insert(i as usize, char::from_u32(n)?);
+        i += 1;
+    }
+
+    Some(output.into_iter().collect())
+}
+
+/// Encode a Unicode string as Punycode, without the `xn--` prefix.
+///
+/// Returns `None` when `input` is too long to encode without overflow.
+///
+/// # Examples
+///
+/// ```
+/// use rfc3986::punycode;
+/// assert_eq!(Some("mnchen-3ya".to_string()), punycode::encode("m\u{fc}nchen"));
+/// ```
+pub fn encode(input: &str) -> Option<String> {
+    let code_points: Vec<u32> = input.chars().map(|character| character as u32).collect();
+    let mut output: String = input.chars().filter(|character| character.is_ascii()).collect();
+    let basic_length = output.len() as u32;
+    if basic_length > 0 {
+        output.push('-');
+    }
+
+    let mut n = INITIAL_N;
+    let mut delta: u32 = 0;
+    let mut bias = INITIAL_BIAS;
+    let mut handled = basic_length;
+
+    while (handled as usize) < code_points.len() {
+        let m = *code_points.iter().filter(|&&code_point| code_point >= n).min()?;
+        delta = delta.checked_add((m - n).checked_mul(handled + 1)?)?;
+        n = m;
+        for &code_point in &code_points {
+            if code_point < n {
+                delta = delta.checked_add(1)?;
+            }
+            if code_point == n {
+                let mut q = delta;
+                let mut k = BASE;
+                loop {
+                    let threshold = threshold(k, bias);
+                    if q < threshold {
+                        break;
+                    }
+                    output.push(encode_digit(threshold + (q - threshold) % (BASE - threshold)));
+                    q = (q - threshold) / (BASE - threshold);
+                    k += BASE;
+                }
+                output.push(encode_digit(q));
+                bias = adapt(delta, handled + 1, handled == basic_length);
+                delta = 0;
+                handled += 1;
+            }
+        }
+        delta = delta.checked_add(1)?;
+        n += 1;
+    }
+
+    Some(output)
+}
+
+fn encode_digit(digit: u32) -> char {
+    if digit < 26 {
+        (b'a' + digit as u8) as char
+    } else {
+        (b'0' + (digit - 26) as u8) as char
+    }
+}
+
+fn decode_digit(byte: u8) -> Option<u32> {
+    match byte {
+        b'a'..=b'z' => Some((byte - b'a') as u32),
+        b'A'..=b'Z' => Some((byte - b'A') as u32),
+        b'0'..=b'9' => Some((byte - b'0') as u32 + 26),
+        _ => None,
+    }
+}
+
+fn threshold(k: u32, bias: u32) -> u32 {
+    if k <= bias {
+        T_MIN
+    } else if k >= bias + T_MAX {
+        T_MAX
+    } else {
+        k - bias
+    }
+}
+
+fn adapt(delta: u32, length: u32, first_time: bool) -> u32 {
+    let mut delta = if first_time { delta / DAMP } else { delta / 2 };
+    delta += delta / length;
+    let mut k = 0;
+    while delta > ((BASE - T_MIN) * T_MAX) / 2 {
+        delta /= BASE - T_MIN;
+        k += BASE;
+    }
+    k + (BASE - T_MIN + 1) * delta / (delta + SKEW)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, encode};
+
+    #[test]
+    fn it_decodes_rfc3492_samples() {
+        assert_eq!(Some("b\u{fc}cher".to_string()), decode("bcher-kva"));
+        assert_eq!(Some("\u{4ed6}\u{4eec}\u{4e3a}\u{4ec0}\u{4e48}\u{4e0d}\u{8bf4}\u{4e2d}\u{6587}".to_string()),
+                   decode("ihqwcrb4cv8a8dqg056pqjye"));
+    }
+
+    #[test]
+    fn it_decodes_plain_ascii() {
+        assert_eq!(Some("example".to_string()), decode("example-"));
+    }
+
+    #[test]
+    fn it_rejects_invalid_digits() {
+        assert_eq!(None, decode("abc-!!"));
+        assert_eq!(None, decode("m\u{fc}nchen-3ya"));
+    }
+
+    #[test]
+    fn it_encodes_rfc3492_samples() {
+        assert_eq!(Some("bcher-kva".to_string()), encode("b\u{fc}cher"));
+        assert_eq!(Some("ihqwcrb4cv8a8dqg056pqjye".to_string()),
+                   encode("\u{4ed6}\u{4eec}\u{4e3a}\u{4ec0}\u{4e48}\u{4e0d}\u{8bf4}\u{4e2d}\u{6587}"));
+        assert_eq!(Some("example-".to_string()), encode("example"));
+    }
+}
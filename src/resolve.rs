@@ -0,0 +1,323 @@
+//! Resolution of a URI reference against a base `Uri` as described in
+//! https://tools.ietf.org/html/rfc3986#section-5.2.
+use authority::Authority;
+use error::ParseError;
+use normalize::remove_dot_segments;
+use uri::Uri;
+
+/// The five components of a URI reference, borrowed from the reference and
+/// split with the regular expression of appendix B.
+struct ReferenceParts<'a> {
+    scheme: Option<&'a str>,
+    authority: Option<&'a str>,
+    path: &'a str,
+    query: Option<&'a str>,
+    fragment: Option<&'a str>,
+}
+
+impl Uri {
+    /// Resolve `reference` against this Uri as its base, following the
+    /// strict algorithm of section 5.2.2.
+    ///
+    /// The reference is split into borrowed slices rather than parsed into
+    /// a `Uri` of its own, so the only allocations are the components of
+    /// the result. Components inherited from the base are cloned only when
+    /// the reference does not replace them, which keeps resolving many links
+    /// against the same base cheap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rfc3986::uri::Uri;
+    /// let base = Uri::from_str("http://a/b/c/d;p?q");
+    /// assert_eq!("http://a/b/c/g?y", base.resolve("g?y").unwrap().to_string());
+    /// assert_eq!("http://a/g", base.resolve("../../g").unwrap().to_string());
+    /// ```
+    pub fn resolve(&self, reference: &str) -> Result<Uri, ParseError> {
+        let parts = split_reference(reference);
+        let fragment = parts.fragment.map(|fragment| fragment.to_string());
+
+        if let Some(scheme) = parts.scheme {
+            let mut target = match parts.authority {
+                Some(authority) => parse_authority(authority)?,
+                None => Uri::empty(),
+            };
+            target.scheme = Some(scheme.to_string());
+            target.path = non_empty(remove_dot_segments(parts.path));
+            target.query = parts.query.map(|query| query.to_string());
+            target.fragment = fragment;
+            return Ok(target);
+        }
+
+        if let Some(authority) = parts.authority {
+            let mut target = parse_authority(authority)?;
+            target.scheme = self.scheme.clone();
+            target.path = non_empty(remove_dot_segments(parts.path));
+            target.query = parts.query.map(|query| query.to_string());
+            target.fragment = fragment;
+            return Ok(target);
+        }
+
+        let (path, query) = if parts.path.is_empty() {
+            let query = match parts.query {
+                Some(query) => Some(query.to_string()),
+                None => self.query.clone(),
+            };
+            (self.path.clone(), query)
+        } else if parts.path.starts_with('/') {
+            (non_empty(remove_dot_segments(parts.path)),
+             parts.query.map(|query| query.to_string()))
+        } else {
+            (non_empty(remove_dot_segments(&self.merge(parts.path))),
+             parts.query.map(|query| query.to_string()))
+        };
+        Ok(Uri {
+            scheme: self.scheme.clone(),
+            userinfo: self.userinfo.clone(),
+            host: self.host.clone(),
+            port: self.port,
+            path,
+            query,
+            fragment,
+            empty_authority: self.empty_authority,
+            raw_port: self.raw_port.clone(),
+        })
+    }
+
+    /// Resolve an already split `reference` against this Uri as its base,
+    /// following the algorithm of section 5.2.2.
+    ///
+    /// When `strict` is unset, a reference whose scheme matches the base's,
+    /// ignoring case, is treated as if it had no scheme, for backwards
+    /// compatibility with parsers that did so. `Uri::parse` reads the first
+    /// segment of a relative reference like `g/h` as a host, so references
+    /// that start out as strings should be given to `resolve` instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rfc3986::uri::Uri;
+    /// let base = Uri::from_str("http://a/b/c/d;p?q");
+    /// let reference = Uri::from_str("//g/x/../y");
+    /// assert_eq!("http://g/y", base.resolve_uri(&reference, true).to_string());
+    /// ```
+    pub fn resolve_uri(&self, reference: &Uri, strict: bool) -> Uri {
+        let scheme = reference.scheme.as_ref().filter(|scheme| {
+            strict || !self.scheme.as_ref().is_some_and(|base| base.eq_ignore_ascii_case(scheme))
+        });
+        let path = reference.path.as_ref().map_or("", |path| path.as_str());
+        let mut target = if scheme.is_some() || reference.has_authority() {
+            Uri {
+                scheme: scheme.or(self.scheme.as_ref()).cloned(),
+                path: non_empty(remove_dot_segments(path)),
+                ..reference.clone()
+            }
+        } else {
+            let (path, query) = if path.is_empty() {
+                (self.path.clone(), reference.query.clone().or_else(|| self.query.clone()))
+            } else if path.starts_with('/') {
+                (non_empty(remove_dot_segments(path)), reference.query.clone())
+            } else {
+                (non_empty(remove_dot_segments(&self.merge(path))), reference.query.clone())
+            };
+            Uri { path, query, ..self.clone() }
+        };
+        target.fragment = reference.fragment.clone();
+        target
+    }
+
+    /// Resolve each of `paths` against this Uri as its base with `resolve`,
+    /// e.g., to turn the relative links of a sitemap into absolute URIs.
+    ///
+    /// The first reference that cannot be resolved, which can only happen
+    /// when it has an invalid authority, is reported as its `ParseError`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rfc3986::uri::Uri;
+    /// let base = Uri::from_str("https://example.com/docs/index.html");
+    /// let uris = base.expand_paths(&["intro.html", "/about"]).unwrap();
+    /// assert_eq!("https://example.com/docs/intro.html", uris[0].to_string());
+    /// assert_eq!("https://example.com/about", uris[1].to_string());
+    /// ```
+    pub fn expand_paths(&self, paths: &[&str]) -> Result<Vec<Uri>, ParseError> {
+        paths.iter().map(|path| self.resolve(path)).collect()
+    }
+
+    /// Merge a relative-path reference with the path of this Uri as in
+    /// section 5.2.3.
+    fn merge(&self, path: &str) -> String {
+        let base = self.path.as_ref().map_or("", |path| path.as_str());
+        let directory = match base.rfind('/') {
+            Some(slash) => &base[..slash + 1],
+            None if self.has_authority() => "/",
+            None => "",
+        };
+        let mut merged = String::with_capacity(directory.len() + path.len());
+        merged.push_str(directory);
+        merged.push_str(path);
+        merged
+    }
+
+    /// A Uri with every component absent.
+    fn empty() -> Uri {
+        Uri {
+            scheme: None,
+            userinfo: None,
+            host: String::new(),
+            port: None,
+            path: None,
+            query: None,
+            fragment: None,
+            empty_authority: false,
+            raw_port: None,
+        }
+    }
+}
+
+/// Split `reference` into its components without allocating.
+fn split_reference(reference: &str) -> ReferenceParts<'_> {
+    let (rest, fragment) = match reference.find('#') {
+        Some(hash) => (&reference[..hash], Some(&reference[hash + 1..])),
+        None => (reference, None),
+    };
+    let (rest, query) = match rest.find('?') {
+        Some(question) => (&rest[..question], Some(&rest[question + 1..])),
+        None => (rest, None),
+    };
+    // A : after the first / belongs to the path, e.g., ./a:b
+    let (scheme, rest) = match rest.find(':') {
+        Some(colon) if colon > 0 && !rest[..colon].contains('/') => {
+            (Some(&rest[..colon]), &rest[colon + 1..])
+        }
+        _ => (None, rest),
+    };
+    let (authority, path) = match rest.strip_prefix("//") {
+        Some(rest) => {
+            let path_start = rest.find('/').unwrap_or(rest.len());
+            (Some(&rest[..path_start]), &rest[path_start..])
+        }
+        None => (None, rest),
+    };
+    ReferenceParts { scheme, authority, path, query, fragment }
+}
+
+/// Parse the userinfo, host and port out of `authority`.
+fn parse_authority(authority: &str) -> Result<Uri, ParseError> {
+    let Authority { userinfo, host, port } = Authority::parse(authority)?;
+    let empty_authority = userinfo.is_none() && host.is_empty() && port.is_none();
+    Ok(Uri { userinfo, host, port, empty_authority, ..Uri::empty() })
+}
+
+fn non_empty(path: String) -> Option<String> {
+    if path.is_empty() { None } else { Some(path) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_authority, split_reference};
+    use uri::Uri;
+
+    /// Build the reference as a `Uri` from its split components, since
+    /// `Uri::parse` cannot parse every relative reference.
+    fn reference_uri(reference: &str) -> Uri {
+        let parts = split_reference(reference);
+        let mut uri = match parts.authority {
+            Some(authority) => parse_authority(authority).unwrap(),
+            None => Uri::empty(),
+        };
+        uri.scheme = parts.scheme.map(|scheme| scheme.to_string());
+        uri.path = if parts.path.is_empty() { None } else { Some(parts.path.to_string()) };
+        uri.query = parts.query.map(|query| query.to_string());
+        uri.fragment = parts.fragment.map(|fragment| fragment.to_string());
+        uri
+    }
+
+    fn assert_resolves(reference: &str, into: &str) {
+        let base = Uri::from_str("http://a/b/c/d;p?q");
+        assert_eq!(into, base.resolve(reference).unwrap().to_string(), "resolving {}", reference);
+        assert_eq!(into, base.resolve_uri(&reference_uri(reference), true).to_string(),
+                   "resolving {} as a Uri", reference);
+    }
+
+    #[test]
+    fn it_resolves_the_normal_examples() {
+        assert_resolves("g:h", "g:h");
+        assert_resolves("g", "http://a/b/c/g");
+        assert_resolves("./g", "http://a/b/c/g");
+        assert_resolves("g/", "http://a/b/c/g/");
+        assert_resolves("/g", "http://a/g");
+        assert_resolves("//g", "http://g");
+        assert_resolves("?y", "http://a/b/c/d;p?y");
+        assert_resolves("g?y", "http://a/b/c/g?y");
+        assert_resolves("#s", "http://a/b/c/d;p?q#s");
+        assert_resolves("g#s", "http://a/b/c/g#s");
+        assert_resolves("g?y#s", "http://a/b/c/g?y#s");
+        assert_resolves(";x", "http://a/b/c/;x");
+        assert_resolves("g;x", "http://a/b/c/g;x");
+        assert_resolves("g;x?y#s", "http://a/b/c/g;x?y#s");
+        assert_resolves("", "http://a/b/c/d;p?q");
+        assert_resolves(".", "http://a/b/c/");
+        assert_resolves("./", "http://a/b/c/");
+        assert_resolves("..", "http://a/b/");
+        assert_resolves("../", "http://a/b/");
+        assert_resolves("../g", "http://a/b/g");
+        assert_resolves("../..", "http://a/");
+        assert_resolves("../../", "http://a/");
+        assert_resolves("../../g", "http://a/g");
+    }
+
+    #[test]
+    fn it_resolves_the_abnormal_examples() {
+        assert_resolves("../../../g", "http://a/g");
+        assert_resolves("../../../../g", "http://a/g");
+        assert_resolves("/./g", "http://a/g");
+        assert_resolves("/../g", "http://a/g");
+        assert_resolves("g.", "http://a/b/c/g.");
+        assert_resolves(".g", "http://a/b/c/.g");
+        assert_resolves("g..", "http://a/b/c/g..");
+        assert_resolves("..g", "http://a/b/c/..g");
+        assert_resolves("./../g", "http://a/b/g");
+        assert_resolves("./g/.", "http://a/b/c/g/");
+        assert_resolves("g/./h", "http://a/b/c/g/h");
+        assert_resolves("g/../h", "http://a/b/c/h");
+        assert_resolves("g;x=1/./y", "http://a/b/c/g;x=1/y");
+        assert_resolves("g;x=1/../y", "http://a/b/c/y");
+        assert_resolves("g?y/./x", "http://a/b/c/g?y/./x");
+        assert_resolves("g?y/../x", "http://a/b/c/g?y/../x");
+        assert_resolves("g#s/./x", "http://a/b/c/g#s/./x");
+        assert_resolves("g#s/../x", "http://a/b/c/g#s/../x");
+        assert_resolves("http:g", "http:g");
+    }
+
+    #[test]
+    fn it_resolves_a_same_scheme_reference_when_not_strict() {
+        let base = Uri::from_str("http://a/b/c/d;p?q");
+        assert_eq!("http:g", base.resolve_uri(&reference_uri("http:g"), true).to_string());
+        assert_eq!("http://a/b/c/g", base.resolve_uri(&reference_uri("http:g"), false).to_string());
+        assert_eq!("https:g", base.resolve_uri(&reference_uri("https:g"), false).to_string());
+    }
+
+    #[test]
+    fn it_expands_paths_for_a_sitemap() {
+        let base = Uri::from_str("https://example.com/blog/2024/index.html?page=2");
+        let uris: Vec<String> = base.expand_paths(&["post-1.html", "../2023/", "/", "./a/b?c", "#top"])
+            .unwrap()
+            .iter()
+            .map(|uri| uri.to_string())
+            .collect();
+        assert_eq!(vec!["https://example.com/blog/2024/post-1.html",
+                        "https://example.com/blog/2023/",
+                        "https://example.com/",
+                        "https://example.com/blog/2024/a/b?c",
+                        "https://example.com/blog/2024/index.html?page=2#top"], uris);
+        assert!(base.expand_paths(&["a", "//example.com:x/"]).is_err());
+    }
+
+    #[test]
+    fn it_resolves_against_a_base_without_a_path() {
+        let base = Uri::from_str("http://example.com");
+        assert_eq!("http://example.com/g", base.resolve("g").unwrap().to_string());
+    }
+}
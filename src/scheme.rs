@@ -0,0 +1,86 @@
+//! Facts about well-known URI schemes.
+
+/// The default ports of well-known schemes, as registered with IANA.
+static DEFAULT_PORTS: &[(&str, u16)] = &[
+    ("ftp", 21),
+    ("ssh", 22),
+    ("sftp", 22),
+    ("telnet", 23),
+    ("gopher", 70),
+    ("http", 80),
+    ("ws", 80),
+    ("nntp", 119),
+    ("imap", 143),
+    ("ldap", 389),
+    ("https", 443),
+    ("wss", 443),
+    ("rtsp", 554),
+    ("ldaps", 636),
+    ("git", 9418),
+];
+
+/// The special-use names reserved by RFC 2606 and RFC 6762, which should
+/// only appear in placeholder URIs when used as a scheme.
+static SPECIAL_USE: &[&str] = &["example", "invalid", "local", "test"];
+
+/// List the well-known schemes with their default ports, ordered by port.
+///
+/// # Examples
+///
+/// ```
+/// use rfc3986::scheme::well_known_ports;
+/// assert!(well_known_ports().contains(&("https", 443)));
+/// ```
+pub fn well_known_ports() -> &'static [(&'static str, u16)] {
+    DEFAULT_PORTS
+}
+
+/// Look up the default port of `scheme`, ignoring case.
+///
+/// # Examples
+///
+/// ```
+/// use rfc3986::scheme::default_port_for_scheme;
+/// assert_eq!(Some(443), default_port_for_scheme("HTTPS"));
+/// assert_eq!(None, default_port_for_scheme("mailto"));
+/// ```
+pub fn default_port_for_scheme(scheme: &str) -> Option<u16> {
+    DEFAULT_PORTS.iter()
+        .find(|&&(name, _)| name.eq_ignore_ascii_case(scheme))
+        .map(|&(_, port)| port)
+}
+
+/// Check whether `scheme` is a special-use name, ignoring case.
+pub(crate) fn is_special_use_scheme(scheme: &str) -> bool {
+    SPECIAL_USE.iter().any(|name| name.eq_ignore_ascii_case(scheme))
+}
+
+/// Check whether `port` is the default port of any well-known scheme.
+pub(crate) fn is_well_known_port(port: u16) -> bool {
+    DEFAULT_PORTS.iter().any(|&(_, default)| default == port)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{default_port_for_scheme, well_known_ports};
+
+    #[test]
+    fn it_lists_the_well_known_ports() {
+        let ports = well_known_ports();
+        assert!(ports.contains(&("http", 80)));
+        assert!(ports.contains(&("https", 443)));
+        assert!(ports.contains(&("ftp", 21)));
+        assert!(ports.contains(&("ssh", 22)));
+    }
+
+    #[test]
+    fn it_looks_up_default_ports() {
+        assert_eq!(Some(80), default_port_for_scheme("http"));
+        assert_eq!(Some(443), default_port_for_scheme("https"));
+        assert_eq!(Some(21), default_port_for_scheme("ftp"));
+        assert_eq!(Some(80), default_port_for_scheme("ws"));
+        assert_eq!(Some(443), default_port_for_scheme("WSS"));
+        assert_eq!(Some(22), default_port_for_scheme("ssh"));
+        assert_eq!(None, default_port_for_scheme("urn"));
+    }
+}
@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use uri::Uri;
+
+/// A `Uri` whose components are reference-counted, so that cloning it is
+/// O(1) no matter how long the URI is.
+///
+/// This is useful when the same parsed URI is shared between many threads
+/// or tasks. Convert with `SharedUri::from` and `SharedUri::to_uri`.
+///
+/// # Examples
+///
+/// ```
+/// use rfc3986::shared_uri::SharedUri;
+/// use rfc3986::uri::Uri;
+/// let shared = SharedUri::from(Uri::from_str("https://example.com/a"));
+/// let copy = shared.clone();
+/// assert_eq!("example.com", &*copy.host);
+/// assert_eq!(Uri::from_str("https://example.com/a"), copy.to_uri());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct SharedUri {
+    pub scheme: Option<Arc<str>>,
+    pub userinfo: Option<Arc<str>>,
+    pub host: Arc<str>,
+    pub port: Option<u16>,
+    pub path: Option<Arc<str>>,
+    pub query: Option<Arc<str>>,
+    pub fragment: Option<Arc<str>>,
+    pub empty_authority: bool,
+    pub raw_port: Option<Arc<str>>,
+}
+
+impl SharedUri {
+    /// Copy the components back into an owned `Uri`.
+    pub fn to_uri(&self) -> Uri {
+        Uri {
+            scheme: self.scheme.as_ref().map(|scheme| scheme.to_string()),
+            userinfo: self.userinfo.as_ref().map(|userinfo| userinfo.to_string()),
+            host: self.host.to_string(),
+            port: self.port,
+            path: self.path.as_ref().map(|path| path.to_string()),
+            query: self.query.as_ref().map(|query| query.to_string()),
+            fragment: self.fragment.as_ref().map(|fragment| fragment.to_string()),
+            empty_authority: self.empty_authority,
+            raw_port: self.raw_port.as_ref().map(|raw_port| raw_port.to_string()),
+        }
+    }
+}
+
+impl From<Uri> for SharedUri {
+    fn from(uri: Uri) -> SharedUri {
+        SharedUri {
+            scheme: uri.scheme.map(Arc::from),
+            userinfo: uri.userinfo.map(Arc::from),
+            host: Arc::from(uri.host),
+            port: uri.port,
+            path: uri.path.map(Arc::from),
+            query: uri.query.map(Arc::from),
+            fragment: uri.fragment.map(Arc::from),
+            empty_authority: uri.empty_authority,
+            raw_port: uri.raw_port.map(Arc::from),
+        }
+    }
+}
+
+impl From<SharedUri> for Uri {
+    fn from(uri: SharedUri) -> Uri {
+        uri.to_uri()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::SharedUri;
+    use uri::Uri;
+
+    #[test]
+    fn it_round_trips_through_a_uri() {
+        let uri = Uri::from_str("https://user@example.com:8443/a/b?c=d#e");
+        let shared = SharedUri::from(uri.clone());
+        assert_eq!(uri, Uri::from(shared));
+    }
+
+    #[test]
+    fn it_shares_components_between_clones() {
+        let shared = SharedUri::from(Uri::from_str("https://example.com/a/b"));
+        let copy = shared.clone();
+        assert!(Arc::ptr_eq(&shared.host, &copy.host));
+        assert!(Arc::ptr_eq(shared.path.as_ref().unwrap(), copy.path.as_ref().unwrap()));
+    }
+}
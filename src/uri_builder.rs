@@ -1,5 +1,13 @@
 use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use std::path::Path;
 
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use encode::{encode_path_segment, encode_userinfo};
+use error::ParseError;
+use host::validate_host;
 use uri::Uri;
 
 /// The `UriBuilder` struct is used to construct instances of the `Uri` class.
@@ -18,7 +26,7 @@ use uri::Uri;
 /// assert_eq!(Some("https".to_string()), uri.scheme);
 /// assert_eq!(Some("username".to_string()), uri.userinfo);
 /// assert_eq!("example.com".to_string(), uri.host);
-/// assert_eq!(Some("login".to_string()), uri.path);
+/// assert_eq!(Some("/login".to_string()), uri.path);
 /// ```
 pub struct UriBuilder {
     scheme: Option<String>,
@@ -30,6 +38,11 @@ pub struct UriBuilder {
     fragment: Option<String>,
 }
 
+impl Default for UriBuilder {
+    fn default() -> UriBuilder {
+        UriBuilder::new()
+    }
+}
 
 impl UriBuilder {
     /// Create a new UriBuilder struct with some default (empty) values.
@@ -56,6 +69,9 @@ impl UriBuilder {
 
     /// Add a scheme to the Uri under construction.
     ///
+    /// Schemes are case-insensitive, so the scheme is stored in its
+    /// canonical lowercase form.
+    ///
     /// # Examples
     ///
     /// ```
@@ -65,14 +81,24 @@ impl UriBuilder {
     ///             .finalize();
     /// assert_eq!(Some("https".to_string()), uri.scheme);
     /// ```
+    ///
+    /// ```
+    /// use rfc3986::uri_builder::UriBuilder;
+    /// let uri = UriBuilder::new()
+    ///             .add_scheme("HTTPS".to_string())
+    ///             .finalize();
+    /// assert_eq!(Some("https".to_string()), uri.scheme);
+    /// ```
     pub fn add_scheme(&mut self, scheme: String) -> &mut UriBuilder {
-        self.scheme = Some(scheme);
+        self.scheme = Some(scheme.to_ascii_lowercase());
         self
     }
 
     /// Add the user information to the Uri under construction.
     ///
     /// The username is required but the password argument is optional.
+    /// Both are percent-encoded with `encode_userinfo`, so a `:` or `@` in
+    /// either survives a round trip.
     ///
     /// # Examples
     ///
@@ -92,18 +118,27 @@ impl UriBuilder {
     ///             .finalize();
     /// assert_eq!(Some("user".to_string()), uri.userinfo);
     /// ```
+    ///
+    /// ```
+    /// use rfc3986::uri_builder::UriBuilder;
+    /// let uri = UriBuilder::new()
+    ///             .add_userinfo("user".to_string(), Some("p@ss:word".to_string()))
+    ///             .finalize();
+    /// assert_eq!(Some("user:p%40ss%3Aword".to_string()), uri.userinfo);
+    /// ```
     pub fn add_userinfo(&mut self,
                         username: String,
                         password: Option<String>) -> &mut UriBuilder {
-        // TODO(sigmavirus24): Encode username and password
+        let username = encode_userinfo(&username);
         self.userinfo = Some(match password {
-            Some(password_str) => format!("{}:{}", username, password_str),
+            Some(password_str) => format!("{}:{}", username, encode_userinfo(&password_str)),
             None => username,
         });
         self
     }
 
-    /// Add the host to the Uri under construction.
+    /// Add the host to the Uri under construction. The host is checked by
+    /// `try_finalize`, not here.
     ///
     /// # Examples
     ///
@@ -123,11 +158,45 @@ impl UriBuilder {
     /// assert_eq!("127.0.0.1".to_string(), uri.host);
     /// ```
     pub fn add_host(&mut self, host: String) -> &mut UriBuilder {
-        // TODO(sigmavirus24): Verify the host is valid
         self.host = host;
         self
     }
 
+    /// Add the host to the Uri under construction, rewriting a numeric IPv4
+    /// address in any of its legacy forms to the canonical dotted quad.
+    ///
+    /// Like the WHATWG URL Standard, each part may be decimal, octal with a
+    /// leading `0` or hexadecimal with a leading `0x`, and the last part
+    /// fills the bytes that the fewer than four parts leave, so `0x7f.1`,
+    /// `0177.0.0.1` and `2130706433` are all `127.0.0.1`. A host with a part
+    /// that is not a number passes through unchanged, while a numeric one
+    /// with a part out of range is reported as `ParseError::InvalidIpv4`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rfc3986::uri_builder::UriBuilder;
+    /// let uri = UriBuilder::new()
+    ///             .add_host_canonical_ip("0177.0.0.1").unwrap()
+    ///             .finalize();
+    /// assert_eq!("127.0.0.1", uri.host);
+    /// ```
+    ///
+    /// ```
+    /// use rfc3986::uri_builder::UriBuilder;
+    /// let uri = UriBuilder::new()
+    ///             .add_host_canonical_ip("example.com").unwrap()
+    ///             .finalize();
+    /// assert_eq!("example.com", uri.host);
+    /// ```
+    pub fn add_host_canonical_ip(&mut self, host: &str) -> Result<&mut UriBuilder, ParseError> {
+        self.host = match parse_numeric_ipv4(host)? {
+            Some(address) => address.to_string(),
+            None => host.to_string(),
+        };
+        Ok(self)
+    }
+
     /// Add the port number to the Uri under construction.
     ///
     /// # Examples
@@ -153,10 +222,31 @@ impl UriBuilder {
     }
 
     pub fn add_path(&mut self, path: String) -> &mut UriBuilder {
-        self.path = Some(match path.starts_with('/') {
-            true => path[1..].to_string(),
-            false => path,
-        });
+        self.path = Some(path);
+        self
+    }
+
+    /// Add the path of a file on this platform to the Uri under
+    /// construction.
+    ///
+    /// Each component of `path` is percent-encoded and the components are
+    /// joined with `/`. On Windows both `\` and `/` separate components,
+    /// and an absolute path starting with a drive letter gains a leading
+    /// `/`, so `C:\a b` becomes `/C:/a%20b`. Components that are not
+    /// valid Unicode are converted lossily.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use rfc3986::uri_builder::UriBuilder;
+    /// let uri = UriBuilder::new()
+    ///             .add_path_from_os(Path::new("/srv/my files/index.html"))
+    ///             .finalize();
+    /// assert_eq!(Some("/srv/my%20files/index.html".to_string()), uri.path);
+    /// ```
+    pub fn add_path_from_os(&mut self, path: &Path) -> &mut UriBuilder {
+        self.path = Some(os_path_to_uri_path(&path.to_string_lossy(), cfg!(windows)));
         self
     }
 
@@ -207,10 +297,10 @@ impl UriBuilder {
                          query_map: &HashMap<String, String>) -> &mut UriBuilder {
         let mut query = String::new();
         for (key, value) in query_map {
-            if query.len() > 0 {
-                query = query + "&";
+            if !query.is_empty() {
+                query += "&";
             }
-            query = query + &format!("{}={}", key, value);
+            query += &format!("{}={}", key, value);
         }
         self.query = Some(query);
         self
@@ -237,15 +327,54 @@ impl UriBuilder {
         let mut query = String::new();
         for pair in query_list {
             let (key, value) = (pair[0], pair[1]);
-            if query.len() > 0 {
-                query = query + "&";
+            if !query.is_empty() {
+                query += "&";
             }
-            query = query + &format!("{}={}", key, value);
+            query += &format!("{}={}", key, value);
         }
         self.query = Some(query);
         self
     }
 
+    /// Serialize `value`, such as a struct of request parameters, into an
+    /// `application/x-www-form-urlencoded` query string and add it to the
+    /// Uri. Fields appear in declaration order and spaces become `+`.
+    ///
+    /// This requires the `serde` feature. A value that cannot be written as
+    /// flat key/value pairs, such as one with a nested struct, is reported
+    /// as `ParseError::InvalidQuery`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #[macro_use]
+    /// extern crate serde_derive;
+    /// extern crate rfc3986;
+    ///
+    /// use rfc3986::uri_builder::UriBuilder;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Q {
+    ///     page: u32,
+    ///     sort: String,
+    /// }
+    ///
+    /// fn main() {
+    ///     let uri = UriBuilder::new()
+    ///                 .add_query_struct(&Q { page: 2, sort: "asc".to_string() })
+    ///                 .unwrap()
+    ///                 .finalize();
+    ///     assert_eq!(Some("page=2&sort=asc".to_string()), uri.query);
+    /// }
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn add_query_struct<T: Serialize>(&mut self, value: &T) -> Result<&mut UriBuilder, ParseError> {
+        let query = serde_urlencoded::to_string(value)
+            .map_err(|error| ParseError::InvalidQuery(error.to_string()))?;
+        self.query = Some(query);
+        Ok(self)
+    }
+
     /// Finalize the `UriBuilder` and create a `Uri` from it.
     ///
     /// # Examples
@@ -257,15 +386,210 @@ impl UriBuilder {
     ///             .finalize();
     /// assert_eq!("example.com".to_string(), uri.generate_authority());
     /// ```
+    ///
+    /// Leaving out the scheme builds a protocol-relative URI:
+    ///
+    /// ```
+    /// use rfc3986::uri_builder::UriBuilder;
+    /// let uri = UriBuilder::new()
+    ///             .add_host("cdn.example.com".to_string())
+    ///             .add_path("/app.js".to_string())
+    ///             .finalize();
+    /// assert_eq!("//cdn.example.com/app.js", uri.to_string());
+    /// ```
     pub fn finalize(&self) -> Uri {
         Uri {
             scheme: self.scheme.clone(),
             userinfo: self.userinfo.clone(),
             host: self.host.clone(),
-            port: self.port.clone(),
+            port: self.port,
             path: self.path.clone(),
             query: self.query.clone(),
             fragment: self.fragment.clone(),
+            empty_authority: false,
+            raw_port: None,
+        }
+    }
+
+    /// Finalize the `UriBuilder` like `finalize`, after checking the host
+    /// with `rfc3986::host::validate_host`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rfc3986::error::ParseError;
+    /// use rfc3986::uri_builder::UriBuilder;
+    /// let uri = UriBuilder::new()
+    ///             .add_host("[::1]".to_string())
+    ///             .try_finalize();
+    /// assert_eq!("[::1]", uri.unwrap().host);
+    ///
+    /// let uri = UriBuilder::new()
+    ///             .add_host("exa mple.com".to_string())
+    ///             .try_finalize();
+    /// assert_eq!(Err(ParseError::InvalidHost("exa mple.com".to_string())), uri);
+    /// ```
+    pub fn try_finalize(&self) -> Result<Uri, ParseError> {
+        validate_host(&self.host)?;
+        Ok(self.finalize())
+    }
+}
+
+/// Convert an OS path to a URI path, splitting on `\` as well as `/` and
+/// rooting a leading drive letter when `windows` is set.
+pub(crate) fn os_path_to_uri_path(path: &str, windows: bool) -> String {
+    let separators: &[char] = if windows { &['\\', '/'] } else { &['/'] };
+    let segments: Vec<String> = path.split(separators)
+        .map(encode_path_segment)
+        .collect();
+    let uri_path = segments.join("/");
+    let has_drive = windows && path.len() >= 2 && path.as_bytes()[0].is_ascii_alphabetic() &&
+        path.as_bytes()[1] == b':';
+    if has_drive {
+        format!("/{}", uri_path)
+    } else {
+        uri_path
+    }
+}
+
+/// Parse `host` as an IPv4 address in decimal, octal or hexadecimal parts,
+/// returning `None` when any part is not a number.
+fn parse_numeric_ipv4(host: &str) -> Result<Option<Ipv4Addr>, ParseError> {
+    let trimmed = match host.strip_suffix('.') {
+        Some(trimmed) if !trimmed.is_empty() => trimmed,
+        _ => host,
+    };
+    let numbers: Option<Vec<u64>> = trimmed.split('.').map(parse_ipv4_number).collect();
+    let numbers = match numbers {
+        Some(numbers) => numbers,
+        None => return Ok(None),
+    };
+    let invalid = || ParseError::InvalidIpv4(host.to_string());
+    let (last, leading) = numbers.split_last().ok_or_else(invalid)?;
+    if leading.len() > 3 || leading.iter().any(|&number| number > 255) ||
+        *last >= 1 << (8 * (4 - leading.len())) {
+        return Err(invalid());
+    }
+    let address = leading.iter()
+        .enumerate()
+        .fold(*last, |address, (index, &number)| address + (number << (8 * (3 - index))));
+    Ok(Some(Ipv4Addr::from(address as u32)))
+}
+
+/// Parse one part of a numeric IPv4 address, in hexadecimal after `0x`, in
+/// octal after a leading `0` and in decimal otherwise. A number too large
+/// for a `u64` is returned as `u64::MAX`, which is out of range anyway.
+fn parse_ipv4_number(part: &str) -> Option<u64> {
+    let (digits, radix) = if part.starts_with("0x") || part.starts_with("0X") {
+        (&part[2..], 16)
+    } else if part.len() > 1 && part.starts_with('0') {
+        (&part[1..], 8)
+    } else {
+        (part, 10)
+    };
+    if digits.is_empty() {
+        return if radix == 10 { None } else { Some(0) };
+    }
+    if !digits.chars().all(|c| c.is_digit(radix)) {
+        return None;
+    }
+    Some(u64::from_str_radix(digits, radix).unwrap_or(u64::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{os_path_to_uri_path, parse_numeric_ipv4, UriBuilder};
+    use error::ParseError;
+
+    #[test]
+    fn it_converts_unix_paths() {
+        assert_eq!("/srv/a%20b/c%3Fd", os_path_to_uri_path("/srv/a b/c?d", false));
+        assert_eq!("rel/caf%C3%A9", os_path_to_uri_path("rel/caf\u{e9}", false));
+        assert_eq!("/a%5Cb", os_path_to_uri_path("/a\\b", false));
+    }
+
+    #[test]
+    fn it_converts_windows_paths() {
+        assert_eq!("/C:/Users/a%20b/file.txt",
+                   os_path_to_uri_path("C:\\Users\\a b\\file.txt", true));
+        assert_eq!("/d:/x/y", os_path_to_uri_path("d:/x\\y", true));
+        assert_eq!("rel/file", os_path_to_uri_path("rel\\file", true));
+    }
+
+    #[test]
+    fn it_validates_the_host_when_finalizing() {
+        for host in &["example.com", "192.0.2.1", "[::1]", "1.2.3", ""] {
+            assert!(UriBuilder::new().add_host(host.to_string()).try_finalize().is_ok());
+        }
+        for host in &["exa mple.com", "256.1.1.1", "[::1"] {
+            assert_eq!(Err(ParseError::InvalidHost(host.to_string())),
+                       UriBuilder::new().add_host(host.to_string()).try_finalize());
+        }
+    }
+
+    #[test]
+    fn it_canonicalizes_octal_and_hex_hosts() {
+        for host in &["0177.0.0.1", "0x7f.0.0.1", "0x7F.1", "0177.1", "2130706433",
+                      "0x7f000001", "017700000001", "127.0.0.1."] {
+            let uri = UriBuilder::new().add_host_canonical_ip(host).unwrap().finalize();
+            assert_eq!("127.0.0.1", uri.host, "canonicalizing {}", host);
+        }
+        assert_eq!(Ok(Some("0.0.0.0".parse().unwrap())), parse_numeric_ipv4("0x"));
+    }
+
+    #[test]
+    fn it_passes_non_numeric_hosts_through() {
+        for host in &["example.com", "1.2.3.example", "0x7g.0.0.1", "1..2", "", "[::1]"] {
+            let uri = UriBuilder::new().add_host_canonical_ip(host).unwrap().finalize();
+            assert_eq!(*host, uri.host);
+        }
+    }
+
+    #[test]
+    fn it_rejects_numeric_hosts_out_of_range() {
+        for host in &["256.0.0.1", "1.2.3.4.5", "1.2.65536", "4294967296", "0x100000000"] {
+            assert_eq!(Err(ParseError::InvalidIpv4(host.to_string())), parse_numeric_ipv4(host));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn it_serializes_a_struct_into_the_query() {
+        #[derive(Serialize)]
+        struct Q {
+            page: u32,
+            sort: String,
+            filter: Option<String>,
+        }
+        let uri = UriBuilder::new()
+            .add_host("example.com".to_string())
+            .add_query_struct(&Q { page: 2, sort: "asc".to_string(), filter: None })
+            .unwrap()
+            .finalize();
+        assert_eq!(Some("page=2&sort=asc".to_string()), uri.query);
+
+        let uri = UriBuilder::new()
+            .add_query_struct(&Q { page: 1, sort: "a b&c".to_string(), filter: Some("x".to_string()) })
+            .unwrap()
+            .finalize();
+        assert_eq!(Some("page=1&sort=a+b%26c&filter=x".to_string()), uri.query);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn it_rejects_a_struct_that_is_not_flat() {
+        #[derive(Serialize)]
+        struct Inner {
+            a: u32,
+        }
+        #[derive(Serialize)]
+        struct Outer {
+            inner: Inner,
+        }
+        let mut builder = UriBuilder::new();
+        match builder.add_query_struct(&Outer { inner: Inner { a: 1 } }) {
+            Err(ParseError::InvalidQuery(_)) => (),
+            _ => panic!("expected an invalid query"),
         }
     }
 }